@@ -19,24 +19,70 @@ limitations under the License.*/
 //!
 //! # Overview
 //!
-//! The `ApiClient` is the main entrypoint. It exposes two methods for
-//! sending HTTP requests to the Dark Sky API:
+//! The `ApiClient` is the main entrypoint. It exposes the following
+//! methods for sending HTTP requests to the Dark Sky API:
 //!
 //!   1. `ApiClient::get_forecast(request: ForecastRequest)` makes an
-//!   HTTP request against the API and returns a deserialized response
-//!   containing a weather forecast given the current weather
-//!   conditions.
+//!      HTTP request against the API and returns a deserialized response
+//!      containing a weather forecast given the current weather
+//!      conditions.
 //!
 //!   2. `ApiClient::get_time_machine(request: TimeMachineRequest)`
-//!   makes a request against the API and returns a deserialized
-//!   response containing weather data corresponding to the `time`
-//!   parameter in the `TimeMachineRequest`.
+//!      makes a request against the API and returns a deserialized
+//!      response containing weather data corresponding to the `time`
+//!      parameter in the `TimeMachineRequest`.
+//!
+//!   3. `ApiClient::get_forecasts(requests: Vec<ForecastRequest>)` sends
+//!      several Forecast API requests concurrently and returns their
+//!      deserialized responses in the order they were given.
 //!
 //! For your convenience, there are two builder objects
 //! `ForecastRequestBuilder` and `TimeMachineRequestBuilder` which you
 //! can use to construct `ForecastRequest` and `TimeMachineRequest`
 //! instances.
 //!
+//! # Custom HTTP stacks
+//!
+//! `ApiClient` sends requests through the `HttpTransport` trait, which is
+//! implemented for `reqwest::Client`. Implement it for your own type to route
+//! requests through e.g. retry or tracing middleware. Alternatively,
+//! `ForecastRequest::to_reqwest` and `TimeMachineRequest::to_reqwest` hand you
+//! a `reqwest::RequestBuilder` to send however you like. Note that
+//! `reqwest-middleware` requires reqwest 0.11 or later, so its
+//! `ClientWithMiddleware` can't be used with this crate, which is built on
+//! reqwest 0.10.
+//!
+//! # Compression
+//!
+//! Responses can be large, particularly with `extend=hourly`. Enabling this
+//! crate's `gzip` and/or `brotli` features enables the matching reqwest
+//! features, so that a `reqwest::Client` advertises them in its
+//! `Accept-Encoding` header and transparently decompresses the response
+//! body. Compression can still be turned off for an individual client with
+//! e.g. `reqwest::Client::builder().gzip(false)`. Note that reqwest removes
+//! the `Content-Length` header from decompressed responses, so the size of
+//! the body on the wire is not available.
+//!
+//! # WebAssembly
+//!
+//! The crate builds for `wasm32-unknown-unknown`, where reqwest sends requests
+//! with the browser's `fetch` API. There, `TransportFuture` isn't `Send`, and
+//! `ApiClient::get_forecast_streaming` and the `CachingApiClient` are not
+//! available because reqwest can't stream response bodies and
+//! `std::time::Instant` isn't supported. For the same reason, don't enable
+//! the `tracing` or `metrics` features, which time each request, on WASM.
+//!
+//! # Lean builds
+//!
+//! Everything which deserializes a response body, i.e. `ApiResponse::from_json`
+//! and friends, the `ApiClient` methods returning an `ApiResponse`, and the
+//! `CachingApiClient`, is behind the default `json` feature. Building with
+//! `default-features = false` drops the `serde_json` dependency while keeping
+//! the request builders and `ApiClient::get_forecast` and
+//! `ApiClient::get_time_machine`, which return the raw `reqwest::Response`.
+//! The model objects still implement serde's traits, so the body can be
+//! parsed elsewhere.
+//!
 //! # Examples
 //!
 //! The following example builds a `ForecastRequest` and a
@@ -85,1185 +131,7644 @@ limitations under the License.*/
 extern crate serde_derive;
 
 use std::vec::Vec;
-use std::borrow::Borrow;
+use std::borrow::{Borrow, Cow};
+use std::cmp::Ordering;
+#[cfg(feature = "json")]
+use std::collections::HashMap;
+#[cfg(all(feature = "json", not(target_arch = "wasm32")))]
+use std::collections::VecDeque;
+use std::error::Error;
+use std::str::FromStr;
+use std::fmt;
+use std::future::Future;
 use std::option::Option;
-
-use serde::de::{Deserialize, Deserializer, IntoDeserializer};
+use std::pin::Pin;
+#[cfg(all(feature = "json", not(target_arch = "wasm32")))]
+use std::sync::Mutex;
+use std::time::{Duration, UNIX_EPOCH};
+#[cfg(any(all(feature = "json", not(target_arch = "wasm32")), feature = "tracing",
+          feature = "metrics"))]
+use std::time::Instant;
+
+use serde::de::{self, Deserialize, Deserializer, IntoDeserializer, SeqAccess, Visitor};
 use serde::ser::{Serialize, Serializer};
 
+#[cfg(feature = "json")]
+use futures::stream::{self, StreamExt};
+#[cfg(all(feature = "json", not(target_arch = "wasm32")))]
+use futures::stream::Stream;
+
 use itertools::join;
 
-use reqwest::{Url, Result as ApiResult, Client, Response};
+use reqwest::{Url, Result as ApiResult, Client, RequestBuilder, Response};
+use reqwest::header::{HeaderMap, HeaderValue, CACHE_CONTROL, EXPIRES, USER_AGENT};
+#[cfg(any(feature = "json", feature = "metrics"))]
+use reqwest::StatusCode;
+#[cfg(feature = "json")]
+use reqwest::header::{DATE, IF_MODIFIED_SINCE, LAST_MODIFIED};
+
+#[cfg(feature = "json")]
+use httpdate::HttpDate;
 
 // constants
 
-static FORECAST_URL: &'static str = "https://api.pirateweather.net/forecast";
-static EXCLUDE: &'static str = "exclude";
-static EXTEND: &'static str = "extend";
-static LANG: &'static str = "lang";
-static UNITS: &'static str = "units";
+static FORECAST_URL: &str = "https://api.pirateweather.net/forecast";
+static EXCLUDE: &str = "exclude";
+static EXTEND: &str = "extend";
+static LANG: &str = "lang";
+static UNITS: &str = "units";
+static VERSION: &str = "version";
+static DEFAULT_USER_AGENT: &str = concat!("forecast-rs/", env!("CARGO_PKG_VERSION"));
 
-// api objects
+const METRES_PER_MILE: f64 = 1609.344;
+const HECTOPASCALS_PER_INCH_OF_MERCURY: f64 = 33.863_886_666_7;
+const MILLIMETRES_PER_INCH: f64 = 25.4;
+
+/// The precipitation intensity, in millimetres per hour, at or above which
+/// `DataPoint::is_severe` considers precipitation heavy (0.3 in/h).
+pub const HEAVY_PRECIP_INTENSITY: f64 = 7.6;
+
+/// The number of decimal places the request builders format coordinates with
+/// by default, which is precise to about 0.1m.
+pub const DEFAULT_COORDINATE_PRECISION: usize = 6;
+
+/// The number of requests `ApiClient::get_forecasts` keeps in flight at once.
+pub const DEFAULT_CONCURRENCY: usize = 8;
 
-/// The ApiClient is a thin wrapper around a `reqwest::Client` which
-/// sends requests to the Forecast and Time Machine APIs.
+/// How far apart, in degrees, the coordinates of two responses may be for
+/// `ApiResponse::merge_hourly` to treat them as the same location.
+pub const LOCATION_TOLERANCE: f64 = 0.0001;
+
+// errors
+
+/// Error type returned by `ApiClient` methods which decode the response body
+/// into an `ApiResponse`, and by `ForecastRequestBuilder::try_build`.
 #[derive(Debug)]
-pub struct ApiClient<'a> {
-    client: &'a Client
+pub enum ForecastError {
+    /// The request could not be sent or its body could not be read.
+    Http(reqwest::Error),
+
+    /// The API responded with a non-success status, along with the details it
+    /// gave in the response body.
+    #[cfg(feature = "json")]
+    Api(ApiError),
+
+    /// The response body could not be deserialized into an `ApiResponse`.
+    #[cfg(feature = "json")]
+    Json(serde_json::Error),
+
+    /// The response body could not be deserialized into an `ApiResponse`,
+    /// along with the path of the offending value, e.g.
+    /// `hourly.data[37].temperature`. Only returned with the `verbose-errors`
+    /// feature, which reports such failures in place of `Json`.
+    #[cfg(feature = "json")]
+    Deserialize { path: String, source: serde_json::Error },
+
+    /// The request was rejected before being sent, e.g. because it asks to
+    /// extend a block which it also excludes.
+    InvalidRequest(String),
+
+    /// The response body was larger than the client's `max_response_bytes`,
+    /// which is given here.
+    ResponseTooLarge(usize),
+
+    /// A place name passed to `ForecastRequestBuilder::from_place` couldn't
+    /// be resolved to a single location.
+    #[cfg(feature = "geocode")]
+    Geocode(GeocodeError)
 }
 
-impl<'a> ApiClient<'a> {
-    /// Construct a new ApiClient.
-    pub fn new(client: &'a Client) -> ApiClient<'a> {
-        ApiClient { client }
+impl fmt::Display for ForecastError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ForecastError::Http(ref err) => write!(f, "HTTP error: {}", err),
+            #[cfg(feature = "json")]
+            ForecastError::Api(ref err) => write!(f, "API error: {}", err),
+            #[cfg(feature = "json")]
+            ForecastError::Json(ref err) => write!(f, "JSON error: {}", err),
+            #[cfg(feature = "json")]
+            ForecastError::Deserialize { ref path, ref source } => {
+                write!(f, "JSON error at {}: {}", path, source)
+            },
+            ForecastError::InvalidRequest(ref reason) => write!(f, "invalid request: {}", reason),
+            ForecastError::ResponseTooLarge(limit) => {
+                write!(f, "response body exceeds the limit of {} bytes", limit)
+            },
+            #[cfg(feature = "geocode")]
+            ForecastError::Geocode(ref err) => write!(f, "geocoding error: {}", err)
+        }
     }
+}
 
-    /// Send a [Forecast API](https://darksky.net/dev/docs/forecast)
-    /// request, returns the corresponding Response.
-    ///
-    /// # Errors
-    ///
-    /// This function is a thin wrapper around
-    /// `reqwest::Client.get(..)`, so it will return an error under the
-    /// same conditions in which reqwest would.
-    pub async fn get_forecast<'b, T>(&self, request: T) -> ApiResult<Response>
-        where T : Borrow<ForecastRequest<'b>> + Sized {
-        self.client.get(request.borrow().url.clone())
-            .send().await
+impl Error for ForecastError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            ForecastError::Http(ref err) => Some(err),
+            #[cfg(feature = "json")]
+            ForecastError::Api(ref err) => Some(err),
+            #[cfg(feature = "json")]
+            ForecastError::Json(ref err) => Some(err),
+            #[cfg(feature = "json")]
+            ForecastError::Deserialize { ref source, .. } => Some(source),
+            ForecastError::InvalidRequest(_) | ForecastError::ResponseTooLarge(_) => None,
+            #[cfg(feature = "geocode")]
+            ForecastError::Geocode(ref err) => Some(err)
+        }
     }
+}
 
-    /// Send a [Time Machine
-    /// API](https://darksky.net/dev/docs/time-machine) request,
-    /// returns the corresponding Response.
-    ///
-    /// # Errors
-    ///
-    /// This function is a thin wrapper around
-    /// `reqwest::Client.get(..)`, so it will return an error under the
-    /// same conditions in which reqwest would.
-    pub async fn get_time_machine<'b, T>(&self, request: T) -> ApiResult<Response>
-        where T : Borrow<TimeMachineRequest<'b>> + Sized {
-        self.client.get(request.borrow().url.clone())
-            .send().await
+impl From<reqwest::Error> for ForecastError {
+    fn from(err: reqwest::Error) -> ForecastError {
+        ForecastError::Http(err)
     }
 }
 
-// request model objects and their builders
+#[cfg(feature = "json")]
+impl From<serde_json::Error> for ForecastError {
+    fn from(err: serde_json::Error) -> ForecastError {
+        ForecastError::Json(err)
+    }
+}
 
-/// Model object representing a request to the Forecast API.
-#[derive(Debug, Clone, PartialEq)]
-pub struct ForecastRequest<'a> {
-    api_key: &'a str,
-    latitude: f64,
-    longitude: f64,
-    url: Url,
-    exclude: Vec<ExcludeBlock>,
-    extend: Option<ExtendBy>,
-    lang: Option<Lang>,
-    units: Option<Units>
+#[cfg(feature = "geocode")]
+impl From<GeocodeError> for ForecastError {
+    fn from(err: GeocodeError) -> ForecastError {
+        ForecastError::Geocode(err)
+    }
 }
 
-impl<'a> ForecastRequest<'a> {
-    pub fn new(
-        api_key: &'a str,
-        latitude: f64,
-        longitude: f64,
-        url: Url,
-        exclude: Vec<ExcludeBlock>,
-        extend: Option<ExtendBy>,
-        lang: Option<Lang>,
-        units: Option<Units>
-    ) -> ForecastRequest<'a> {
-        ForecastRequest {
-            api_key,
-            latitude,
-            longitude,
-            url,
-            exclude,
-            extend,
-            lang,
-            units
-        }
+#[cfg(feature = "verbose-errors")]
+impl From<serde_path_to_error::Error<serde_json::Error>> for ForecastError {
+    fn from(err: serde_path_to_error::Error<serde_json::Error>) -> ForecastError {
+        ForecastError::Deserialize { path: err.path().to_string(), source: err.into_inner() }
     }
 }
 
-/// Builder object used to construct a ForecastRequest.
-#[derive(Debug, Clone, PartialEq)]
-pub struct ForecastRequestBuilder<'a> {
-    api_key: &'a str,
-    latitude: f64,
-    longitude: f64,
-    exclude: Vec<ExcludeBlock>,
-    extend: Option<ExtendBy>,
-    lang: Option<Lang>,
-    units: Option<Units>
+/// The details of a non-success response, parsed from a JSON body such as
+/// `{"code": 403, "error": "daily usage limit exceeded"}`.
+#[cfg(feature = "json")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApiError {
+    /// The HTTP status of the response.
+    pub status: StatusCode,
+
+    /// The `code` property of the body, if any.
+    pub code: Option<String>,
+
+    /// The `error` or `message` property of the body. If the body isn't JSON
+    /// this is the whole body, or the status' reason phrase if it is empty.
+    pub message: String
 }
 
-impl<'a> ForecastRequestBuilder<'a> {
-    /// A Forecast API request is constructed with required params
-    /// `api_key`, `latitude`, and `longitude`.
-    pub fn new(api_key: &'a str, latitude: f64, longitude: f64) -> ForecastRequestBuilder {
-        ForecastRequestBuilder {
-            api_key,
-            latitude,
-            longitude,
-            exclude: Vec::new(),
-            extend: None,
-            lang: None,
-            units: None
-        }
-    }
+#[cfg(feature = "json")]
+impl ApiError {
+    fn from_body(status: StatusCode, body: &[u8]) -> ApiError {
+        let json: Option<serde_json::Value> = serde_json::from_slice(body).ok();
+
+        let code = json.as_ref().and_then(|json| match json.get("code") {
+            Some(serde_json::Value::String(code)) => Some(code.clone()),
+            Some(serde_json::Value::Number(code)) => Some(code.to_string()),
+            _ => None
+        });
+
+        let message = json.as_ref()
+            .and_then(|json| json.get("error").or_else(|| json.get("message")))
+            .and_then(serde_json::Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| String::from_utf8_lossy(body).trim().to_string());
+
+        let message = if message.is_empty() {
+            status.canonical_reason().unwrap_or_default().to_string()
+        } else {
+            message
+        };
 
-    /// Add a DataBlock to exclude from the response.
-    pub fn exclude_block(mut self, exclude_block: ExcludeBlock) -> ForecastRequestBuilder<'a> {
-        self.exclude.push(exclude_block);
-        self
+        ApiError { status, code, message }
     }
+}
 
-    /// Add multiple DataBlocks to exclude from the response.
-    pub fn exclude_blocks(mut self, exclude_blocks: &mut Vec<ExcludeBlock>)
-        -> ForecastRequestBuilder<'a> {
-        self.exclude.append(exclude_blocks);
-        self
+#[cfg(feature = "json")]
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.code {
+            Some(ref code) => write!(f, "{} ({}, code {})", self.message, self.status, code),
+            None => write!(f, "{} ({})", self.message, self.status)
+        }
     }
+}
 
-    /// Extend the time window of the response data from 48 hours to
-    /// 168 hours.
-    pub fn extend(mut self, extend: ExtendBy) -> ForecastRequestBuilder<'a> {
-        self.extend = Some(extend);
-        self
-    }
+#[cfg(feature = "json")]
+impl Error for ApiError {}
 
-    /// Set the language for messages in the response data.
-    pub fn lang(mut self, lang: Lang) -> ForecastRequestBuilder<'a> {
-        self.lang = Some(lang);
-        self
+/// Error returned when a `Geocoder` can't resolve a place name to a single
+/// location.
+#[cfg(feature = "geocode")]
+#[derive(Debug)]
+pub enum GeocodeError {
+    /// The geocoder found no place with the given name.
+    NotFound(String),
+
+    /// The geocoder found several places with the given name, e.g. to offer
+    /// the user a choice between them.
+    Ambiguous(String, Vec<Place>),
+
+    /// The geocoder itself failed, e.g. because its service was unreachable.
+    Geocoder(Box<dyn Error + Send + Sync>)
+}
+
+#[cfg(feature = "geocode")]
+impl fmt::Display for GeocodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            GeocodeError::NotFound(ref name) => write!(f, "no place named {:?}", name),
+            GeocodeError::Ambiguous(ref name, ref places) => {
+                write!(f, "{} places named {:?}", places.len(), name)
+            },
+            GeocodeError::Geocoder(ref err) => err.fmt(f)
+        }
     }
+}
 
-    /// Set the measurement units for response data.
-    pub fn units(mut self, units: Units) -> ForecastRequestBuilder<'a> {
-        self.units = Some(units);
-        self
+#[cfg(feature = "geocode")]
+impl Error for GeocodeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            GeocodeError::Geocoder(ref err) => Some(&**err),
+            _ => None
+        }
     }
+}
 
-    /// Finalize the request.
-    pub fn build(self) -> ForecastRequest<'a> {
-        ForecastRequest::new(
-            self.api_key,
-            self.latitude,
-            self.longitude,
-            self.build_url(),
-            self.exclude,
-            self.extend,
-            self.lang,
-            self.units
+/// Error returned by `ApiResponse::merge_hourly` when the two responses are
+/// for different locations.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LocationMismatchError {
+    /// The `(latitude, longitude)` of the response being merged into.
+    pub expected: (f64, f64),
+
+    /// The `(latitude, longitude)` of the response being merged.
+    pub found: (f64, f64)
+}
+
+impl fmt::Display for LocationMismatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "location mismatch: expected ({}, {}), found ({}, {})",
+            self.expected.0, self.expected.1, self.found.0, self.found.1
         )
     }
+}
 
-    fn build_url(&self) -> Url {
-        let url_string = format!(
-            "{base}/{key}/{lat:.16},{long:.16}",
-            base = FORECAST_URL,
-            key = &self.api_key,
-            lat = &self.latitude,
-            long = &self.longitude
-        );
+impl Error for LocationMismatchError {}
 
-        let mut url = Url::parse(&url_string).unwrap();
+/// Error returned when parsing one of the API's short codes, e.g. an
+/// `ExcludeBlock` name, from a string which is not a recognized code.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseCodeError {
+    /// What was being parsed, e.g. `"exclude block"`.
+    pub kind: &'static str,
 
-        {
-            let mut query_pairs = url.query_pairs_mut();
+    /// The unrecognized input.
+    pub code: String
+}
 
-            if !&self.exclude.is_empty() {
-                let excludes = join(
-                    &self.exclude
-                        .iter()
-                        .map(|e| {
-                            let json = serde_json::to_string(e).unwrap();
-                            json.trim_matches('"').to_string()
-                        })
-                        .collect::<Vec<String>>(),
-                    ","
-                );
+impl fmt::Display for ParseCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown {} {:?}", self.kind, self.code)
+    }
+}
 
-                query_pairs.append_pair(EXCLUDE, &excludes);
-            }
+impl Error for ParseCodeError {}
 
-            if let &Some(ref extend) = &self.extend {
-                query_pairs.append_pair(
-                    EXTEND,
-                    serde_json::to_string(&extend).unwrap().trim_matches('"')
-                );
-            }
+/// A problem found in a decoded response by `ApiResponse::validate`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidationIssue {
+    /// The block containing the problem, e.g. `"hourly"`, or `"response"` for
+    /// the top-level properties.
+    pub block: &'static str,
 
-            if let &Some(ref lang) = &self.lang {
-                query_pairs.append_pair(
-                    LANG,
-                    serde_json::to_string(&lang).unwrap().trim_matches('"')
-                );
-            }
+    /// The index of the offending data point within the block's `data`, if
+    /// the problem is in one.
+    pub index: Option<usize>,
 
-            if let &Some(ref units) = &self.units {
-                query_pairs.append_pair(
-                    UNITS,
-                    serde_json::to_string(&units).unwrap().trim_matches('"')
-                );
-            }
-        };
+    /// The offending property, e.g. `"humidity"`.
+    pub field: &'static str,
 
-        url
+    /// A description of the problem.
+    pub message: String
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.index {
+            Some(index) => {
+                write!(f, "{}.data[{}].{}: {}", self.block, index, self.field, self.message)
+            },
+            None => write!(f, "{}.{}: {}", self.block, self.field, self.message)
+        }
     }
 }
 
-/// Model object representing a request to the Time Machine API.
-#[derive(Debug, Clone, PartialEq)]
-pub struct TimeMachineRequest<'a> {
-    api_key: &'a str,
-    latitude: f64,
-    longitude: f64,
-    time: u64,
-    url: Url,
-    exclude: Vec<ExcludeBlock>,
-    lang: Option<Lang>,
-    units: Option<Units>
+// api objects
+
+/// The future returned by `HttpTransport::get_with_headers`.
+#[cfg(not(target_arch = "wasm32"))]
+pub type TransportFuture<'a> = Pin<Box<dyn Future<Output = ApiResult<Response>> + Send + 'a>>;
+
+/// The future returned by `HttpTransport::get_with_headers`. reqwest's
+/// futures aren't `Send` on WASM, where they're driven by the browser's
+/// `fetch`.
+#[cfg(target_arch = "wasm32")]
+pub type TransportFuture<'a> = Pin<Box<dyn Future<Output = ApiResult<Response>> + 'a>>;
+
+/// The HttpTransport sends the GET requests an `ApiClient` makes. It is
+/// implemented for `reqwest::Client`, which is what an `ApiClient` uses by
+/// default; implement it yourself to serve canned responses in tests.
+///
+/// A `reqwest::Response` can be constructed from an `http::Response`, e.g.
+/// `Response::from(http::Response::new(json))`.
+pub trait HttpTransport {
+    /// Send a GET request to `url` with the given headers, e.g. the
+    /// `User-Agent` and `If-Modified-Since`. Implementations must send all of
+    /// them.
+    fn get_with_headers(&self, url: Url, headers: HeaderMap) -> TransportFuture<'_>;
+
+    /// Send a GET request to `url` without any extra headers.
+    fn get(&self, url: Url) -> TransportFuture<'_> {
+        self.get_with_headers(url, HeaderMap::new())
+    }
 }
 
-impl<'a> TimeMachineRequest<'a> {
-    pub fn new(
-        api_key: &'a str,
-        latitude: f64,
-        longitude: f64,
-        time: u64,
-        url: Url,
-        exclude: Vec<ExcludeBlock>,
-        lang: Option<Lang>,
-        units: Option<Units>
-    ) -> TimeMachineRequest<'a> {
-        TimeMachineRequest {
-            api_key,
-            latitude,
-            longitude,
-            time,
-            url,
-            exclude,
-            lang,
-            units
-        }
+impl HttpTransport for Client {
+    fn get_with_headers(&self, url: Url, headers: HeaderMap) -> TransportFuture<'_> {
+        Box::pin(Client::get(self, url).headers(headers).send())
     }
 }
 
-/// Builder object used to construct a TimeMachineRequest.
-#[derive(Debug, Clone, PartialEq)]
-pub struct TimeMachineRequestBuilder<'a> {
-    api_key: &'a str,
-    latitude: f64,
-    longitude: f64,
-    time: u64,
-    exclude: Vec<ExcludeBlock>,
+/// The ApiClient is a thin wrapper around an `HttpTransport`, by default a
+/// `reqwest::Client`, which sends requests to the Forecast and Time Machine
+/// APIs.
+#[derive(Debug)]
+pub struct ApiClient<'a, H: HttpTransport = Client> {
+    client: &'a H,
+    units: Option<Units>,
     lang: Option<Lang>,
-    units: Option<Units>
+    user_agent: HeaderValue,
+    #[cfg(feature = "json")]
+    max_response_bytes: Option<usize>
 }
 
-impl<'a> TimeMachineRequestBuilder<'a> {
-    /// A Time Machine API request is constructed with required params
-    /// `api_key`, `latitude`, `longitude`, and `time`.
-    pub fn new(
-        api_key: &'a str,
-        latitude: f64,
-        longitude: f64,
-        time: u64
-    ) -> TimeMachineRequestBuilder {
-        TimeMachineRequestBuilder {
-            api_key,
-            latitude,
-            longitude,
-            time,
-            exclude: Vec::new(),
-            lang: None,
-            units: None
-        }
+impl<'a, H: HttpTransport> ApiClient<'a, H> {
+    /// Construct a new ApiClient, which identifies itself with a
+    /// `User-Agent` of `forecast-rs/{version}`.
+    pub fn new(client: &'a H) -> ApiClient<'a, H> {
+        ApiClientBuilder::new(client).build()
     }
 
-    /// Add a DataBlock to exclude from the response.
-    pub fn exclude_block(mut self, exclude_block: ExcludeBlock) -> TimeMachineRequestBuilder<'a> {
-        self.exclude.push(exclude_block);
-        self
-    }
+    /// Start building a Forecast API request which uses this client's
+    /// default `Units` and `Lang`, if any. Calling `units` or `lang` on the
+    /// returned builder overrides them.
+    pub fn forecast_request<'b>(
+        &self,
+        api_key: impl Into<Cow<'b, str>>,
+        latitude: f64,
+        longitude: f64
+    ) -> ForecastRequestBuilder<'b> {
+        let mut builder = ForecastRequestBuilder::new(api_key, latitude, longitude);
 
-    /// Add multiple DataBlocks to exclude from the response.
-    pub fn exclude_blocks(
-        mut self,
-        exclude_blocks: &mut Vec<ExcludeBlock>
-    ) -> TimeMachineRequestBuilder<'a> {
-        self.exclude.append(exclude_blocks);
-        self
+        builder.units = self.units;
+        builder.lang = self.lang.clone();
+        builder
     }
 
-    /// Set the language for messages in the response data.
-    pub fn lang(mut self, lang: Lang) -> TimeMachineRequestBuilder<'a> {
-        self.lang = Some(lang);
-        self
-    }
+    /// Start building a Time Machine API request which uses this client's
+    /// default `Units` and `Lang`, if any. Calling `units` or `lang` on the
+    /// returned builder overrides them.
+    pub fn time_machine_request<'b>(
+        &self,
+        api_key: impl Into<Cow<'b, str>>,
+        latitude: f64,
+        longitude: f64,
+        time: u64
+    ) -> TimeMachineRequestBuilder<'b> {
+        let mut builder = TimeMachineRequestBuilder::new(api_key, latitude, longitude, time);
 
-    /// Set the measurement units for response data.
-    pub fn units(mut self, units: Units) -> TimeMachineRequestBuilder<'a> {
-        self.units = Some(units);
-        self
+        builder.units = self.units;
+        builder.lang = self.lang.clone();
+        builder
     }
 
-    /// Finalize the request.
-    pub fn build(self) -> TimeMachineRequest<'a> {
-        TimeMachineRequest::new(
-            self.api_key,
-            self.latitude,
-            self.longitude,
-            self.time,
-            self.build_url(),
-            self.exclude,
-            self.lang,
-            self.units
-        )
+    /// Send a [Forecast API](https://darksky.net/dev/docs/forecast)
+    /// request, returns the corresponding Response.
+    ///
+    /// # Errors
+    ///
+    /// This function is a thin wrapper around
+    /// `reqwest::Client.get(..)`, so it will return an error under the
+    /// same conditions in which reqwest would.
+    pub async fn get_forecast<'b, T>(&self, request: T) -> ApiResult<Response>
+        where T : Borrow<ForecastRequest<'b>> + Sized {
+        self.send(request.borrow().target()).await
     }
 
-    fn build_url(&self) -> Url {
-        let url_string = format!(
-            "{base}/{key}/{lat:.16},{long:.16},{time}",
-            base = FORECAST_URL,
-            key = self.api_key,
-            lat = self.latitude,
-            long = self.longitude,
-            time = self.time
-        );
+    /// Send a [Time Machine
+    /// API](https://darksky.net/dev/docs/time-machine) request,
+    /// returns the corresponding Response.
+    ///
+    /// # Errors
+    ///
+    /// This function is a thin wrapper around
+    /// `reqwest::Client.get(..)`, so it will return an error under the
+    /// same conditions in which reqwest would.
+    pub async fn get_time_machine<'b, T>(&self, request: T) -> ApiResult<Response>
+        where T : Borrow<TimeMachineRequest<'b>> + Sized {
+        self.send(request.borrow().target()).await
+    }
 
-        let mut url = Url::parse(&url_string).unwrap();
+    // Every request is sent through here. With the `tracing` feature each one
+    // is wrapped in a span recording the response status and latency, and with
+    // the `metrics` feature it is counted and timed. The API key is never
+    // recorded.
+    async fn send(&self, target: RequestTarget<'_>) -> ApiResult<Response> {
+        self.send_with_headers(target, HeaderMap::new()).await
+    }
 
-        {
-            let mut query_pairs = url.query_pairs_mut();
+    async fn send_with_headers(
+        &self,
+        target: RequestTarget<'_>,
+        mut headers: HeaderMap
+    ) -> ApiResult<Response> {
+        #[cfg(any(feature = "tracing", feature = "metrics"))]
+        let start = Instant::now();
+
+        headers.insert(USER_AGENT, self.user_agent.clone());
+
+        // reqwest's `Request` owns its `Url`, and reqwest 0.10 only implements
+        // `IntoUrl` for `Url`, `&str` and `&String`, so this single clone is
+        // cheaper than the alternative of parsing `target.url.as_str()` again.
+        let response = self.client.get_with_headers(target.url.clone(), headers);
+
+        #[cfg(feature = "tracing")]
+        let span = tracing::info_span!(
+            "forecast_api_request",
+            endpoint = target.endpoint.as_str(),
+            latitude = target.latitude,
+            longitude = target.longitude,
+            has_api_key = !target.api_key.is_empty(),
+            url = %redacted_url(target.api_key, target.url),
+            status = tracing::field::Empty,
+            latency_ms = tracing::field::Empty
+        );
 
-            if !self.exclude.is_empty() {
-                let excludes = join(
-                    &self.exclude
-                        .iter()
-                        .map(|e| {
-                            let json = serde_json::to_string(e).unwrap();
-                            json.trim_matches('"').to_string()
-                        })
-                        .collect::<Vec<String>>(),
-                    ",",
-                );
+        #[cfg(feature = "tracing")]
+        let response = tracing::Instrument::instrument(response, span.clone());
 
-                query_pairs.append_pair(EXCLUDE, &excludes);
-            }
+        let result = response.await;
 
-            if let &Some(ref lang) = &self.lang {
-                query_pairs.append_pair(
-                    LANG,
-                    serde_json::to_string(&lang).unwrap().trim_matches('"')
-                );
-            }
+        #[cfg(feature = "tracing")]
+        {
+            span.record("latency_ms", start.elapsed().as_millis() as u64);
 
-            if let &Some(ref units) = &self.units {
-                query_pairs.append_pair(
-                    UNITS,
-                    serde_json::to_string(&units).unwrap().trim_matches('"')
-                );
+            if let Ok(ref response) = result {
+                span.record("status", response.status().as_u16());
             }
         }
 
-        url
+        #[cfg(feature = "metrics")]
+        record_metrics(target.endpoint, &result, start.elapsed());
+
+        result
     }
 }
 
-// data model objects
+// The methods which deserialize the response body.
+#[cfg(feature = "json")]
+impl<'a, H: HttpTransport> ApiClient<'a, H> {
+    /// Send several [Forecast API](https://darksky.net/dev/docs/forecast)
+    /// requests concurrently, keeping at most `DEFAULT_CONCURRENCY` of them
+    /// in flight at once. See `get_forecasts_with_concurrency`.
+    pub async fn get_forecasts(
+        &self,
+        requests: Vec<ForecastRequest<'_>>
+    ) -> Vec<Result<ApiResponse, ForecastError>> {
+        self.get_forecasts_with_concurrency(requests, DEFAULT_CONCURRENCY).await
+    }
 
-/// Model object representing an icon for display.
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
-pub enum Icon {
-    #[serde(rename = "clear-day")]
-    ClearDay,
+    /// Send several [Forecast API](https://darksky.net/dev/docs/forecast)
+    /// requests concurrently, keeping at most `concurrency` of them in flight
+    /// at once, and deserialize their responses.
+    ///
+    /// The results are returned in the same order as `requests`.
+    ///
+    /// # Errors
+    ///
+    /// Each request succeeds or fails independently. A request fails with
+    /// `ForecastError::Http` if reqwest returns an error, with
+    /// `ForecastError::Api` if the API responds with a non-success status,
+    /// and with `ForecastError::Json` if the response body cannot be
+    /// deserialized.
+    pub async fn get_forecasts_with_concurrency(
+        &self,
+        requests: Vec<ForecastRequest<'_>>,
+        concurrency: usize
+    ) -> Vec<Result<ApiResponse, ForecastError>> {
+        stream::iter(requests)
+            .map(|request| async move { self.get_api_response(request.target()).await })
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
+    }
 
-    #[serde(rename = "clear-night")]
-    ClearNight,
+    /// Send a [Forecast API](https://darksky.net/dev/docs/forecast)
+    /// request, returning the deserialized response along with the caching
+    /// headers the API sent with it.
+    ///
+    /// # Errors
+    ///
+    /// This function fails under the same conditions as
+    /// `ApiClient::get_forecasts`.
+    pub async fn get_forecast_with_cache_info<'b, T>(
+        &self,
+        request: T
+    ) -> Result<(ApiResponse, CacheInfo), ForecastError>
+        where T : Borrow<ForecastRequest<'b>> + Sized {
+        self.get_api_response_with_cache_info(request.borrow().target()).await
+    }
 
-    #[serde(rename = "rain")]
-    Rain,
+    /// Send a [Forecast API](https://darksky.net/dev/docs/forecast)
+    /// request with an `If-Modified-Since` header of `last_modified`, if
+    /// given, typically the date returned by a previous call for the same
+    /// request. If the API responds `304 Not Modified` the previous response
+    /// can be reused; otherwise the deserialized response is returned along
+    /// with its `Last-Modified` header, or failing that its `Date` header, to
+    /// pass to the next call.
+    ///
+    /// # Errors
+    ///
+    /// This function fails under the same conditions as
+    /// `ApiClient::get_forecasts`.
+    pub async fn get_forecast_conditional<'b, T>(
+        &self,
+        request: T,
+        last_modified: Option<HttpDate>
+    ) -> Result<ConditionalResponse, ForecastError>
+        where T : Borrow<ForecastRequest<'b>> + Sized {
+        let mut headers = HeaderMap::new();
 
-    #[serde(rename = "snow")]
-    Snow,
+        if let Some(last_modified) = last_modified {
+            let value = HeaderValue::from_str(&last_modified.to_string())
+                .expect("HTTP dates are valid header values");
 
-    #[serde(rename = "sleet")]
-    Sleet,
+            headers.insert(IF_MODIFIED_SINCE, value);
+        }
 
-    #[serde(rename = "wind")]
-    Wind,
+        let target = request.borrow().target();
+        let response = self.send_with_headers(target, headers).await?;
 
-    #[serde(rename = "fog")]
-    Fog,
+        if response.status() == StatusCode::NOT_MODIFIED {
+            return Ok(ConditionalResponse::NotModified);
+        }
 
-    #[serde(rename = "cloudy")]
-    Cloudy,
+        let response = self.error_for_status(response).await?;
 
-    #[serde(rename = "partly-cloudy-day")]
-    PartlyCloudyDay,
+        let last_modified = [LAST_MODIFIED, DATE].iter()
+            .filter_map(|name| response.headers().get(name))
+            .filter_map(|value| value.to_str().ok())
+            .find_map(|value| value.parse().ok());
 
-    #[serde(rename = "partly-cloudy-night")]
-    PartlyCloudyNight,
+        let api_response = self.read_api_response(target.endpoint, response).await?;
 
-    #[serde(rename = "hail")]
-    Hail,
+        Ok(ConditionalResponse::Modified(Box::new(api_response), last_modified))
+    }
 
-    #[serde(rename = "thunderstorm")]
-    Thunderstorm,
+    /// Send a GET request to an arbitrary, fully formed `url`, e.g. one
+    /// captured from a log, and deserialize the response. This bypasses the
+    /// request builders entirely, so nothing about `url` is validated: it is
+    /// up to the caller to point it at the API and include the API key.
+    ///
+    /// # Errors
+    ///
+    /// This function fails under the same conditions as
+    /// `ApiClient::get_forecasts`.
+    pub async fn get_forecast_url(&self, url: Url) -> Result<ApiResponse, ForecastError> {
+        self.get_api_response(RequestTarget::from_url(&url)).await
+    }
 
-    #[serde(rename = "tornado")]
-    Tornado
-}
+    /// Send a [Forecast API](https://darksky.net/dev/docs/forecast)
+    /// request, returning the deserialized response along with the `Lang` it
+    /// was requested in, e.g. to notice summaries which fell back to English.
+    ///
+    /// # Errors
+    ///
+    /// This function fails under the same conditions as
+    /// `ApiClient::get_forecasts`.
+    pub async fn get_forecast_parsed<'b, T>(
+        &self,
+        request: T
+    ) -> Result<ParsedResponse, ForecastError>
+        where T : Borrow<ForecastRequest<'b>> + Sized {
+        self.get_parsed_response(request.borrow().target()).await
+    }
 
-/// Model object representing the kind of precipitation occurring at a particular
-/// time.
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
-pub enum PrecipType {
-    #[serde(rename = "rain")]
-    Rain,
+    /// Send a [Time Machine API](https://darksky.net/dev/docs/time-machine)
+    /// request, returning the deserialized response along with the `Lang` it
+    /// was requested in. See `get_forecast_parsed`.
+    ///
+    /// # Errors
+    ///
+    /// This function fails under the same conditions as
+    /// `ApiClient::get_forecasts`.
+    pub async fn get_time_machine_parsed<'b, T>(
+        &self,
+        request: T
+    ) -> Result<ParsedResponse, ForecastError>
+        where T : Borrow<TimeMachineRequest<'b>> + Sized {
+        self.get_parsed_response(request.borrow().target()).await
+    }
 
-    #[serde(rename = "snow")]
-    Snow,
+    /// Send a [Forecast API](https://darksky.net/dev/docs/forecast)
+    /// request, returning a stream of the data points in the response's
+    /// `hourly` block. The body is parsed as it arrives, so only the data
+    /// point currently being read is held in memory rather than the whole
+    /// response. The stream is empty if the response has no `hourly` block.
+    ///
+    /// # Errors
+    ///
+    /// This function fails with `ForecastError::Http` if reqwest returns an
+    /// error, or with `ForecastError::Api` if the API responds with a
+    /// non-success status. Errors reading
+    /// the body or deserializing a data point are yielded by the stream, as is
+    /// `ForecastError::ResponseTooLarge` if the body exceeds the client's
+    /// `max_response_bytes`, after which the stream ends.
+    ///
+    /// Not available on WASM, where reqwest can't read the body in chunks.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn get_forecast_streaming<'b, T>(
+        &self,
+        request: T
+    ) -> Result<impl Stream<Item = Result<DataPoint, ForecastError>>, ForecastError>
+        where T : Borrow<ForecastRequest<'b>> + Sized {
+        let response = self.send(request.borrow().target()).await?;
+        let response = self.error_for_status(response).await?;
+
+        let limit = self.max_response_bytes.unwrap_or(usize::MAX);
+        let state = (Some(response), HourlyDataScanner::new(), VecDeque::new(), 0);
+
+        Ok(stream::unfold(state, move |(mut response, mut scanner, mut pending, mut read)| {
+            async move {
+                loop {
+                    if let Some(data_point) = pending.pop_front() {
+                        return Some((data_point, (response, scanner, pending, read)));
+                    }
+
+                    let chunk = match response {
+                        Some(ref mut response) => response.chunk().await,
+                        None => return None
+                    };
+
+                    match chunk {
+                        Ok(Some(chunk)) if chunk.len() > limit - read => {
+                            response = None;
+                            pending.push_back(Err(ForecastError::ResponseTooLarge(limit)));
+                        },
+                        Ok(Some(chunk)) => {
+                            read += chunk.len();
+                            pending.extend(scanner.feed(&chunk));
+                        },
+                        Ok(None) => response = None,
+                        Err(err) => {
+                            response = None;
+                            pending.push_back(Err(ForecastError::from(err)));
+                        }
+                    }
+                }
+            }
+        }))
+    }
 
-    #[serde(rename = "sleet")]
-    Sleet
-}
+    async fn get_api_response(
+        &self,
+        target: RequestTarget<'_>
+    ) -> Result<ApiResponse, ForecastError> {
+        let (api_response, _) = self.get_api_response_with_cache_info(target).await?;
 
-/// Model object representing a DataBlock to exclude from the response.
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
-pub enum ExcludeBlock {
-    #[serde(rename = "currently")]
-    Currently,
+        Ok(api_response)
+    }
 
-    #[serde(rename = "minutely")]
-    Minutely,
+    async fn get_parsed_response(
+        &self,
+        target: RequestTarget<'_>
+    ) -> Result<ParsedResponse, ForecastError> {
+        Ok(ParsedResponse {
+            response: self.get_api_response(target).await?,
+            requested_lang: requested_lang(target.url)
+        })
+    }
 
-    #[serde(rename = "hourly")]
-    Hourly,
+    async fn get_api_response_with_cache_info(
+        &self,
+        target: RequestTarget<'_>
+    ) -> Result<(ApiResponse, CacheInfo), ForecastError> {
+        let response = self.send(target).await?;
+        let response = self.error_for_status(response).await?;
 
-    #[serde(rename = "daily")]
-    Daily,
+        let cache_info = CacheInfo::from_headers(response.headers());
+        let api_response = self.read_api_response(target.endpoint, response).await?;
 
-    #[serde(rename = "alerts")]
-    Alerts,
+        Ok((api_response, cache_info))
+    }
 
-    #[serde(rename = "flags")]
-    Flags
-}
+    // Reads and deserializes the body of a successful response from
+    // `endpoint`, counting a body that can't be deserialized with the
+    // `metrics` feature.
+    #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+    async fn read_api_response(
+        &self,
+        endpoint: Endpoint,
+        response: Response
+    ) -> Result<ApiResponse, ForecastError> {
+        let api_response = ApiResponse::from_slice(&self.read_body(response).await?);
+
+        #[cfg(feature = "metrics")]
+        {
+            if api_response.is_err() {
+                record_error(endpoint, "json");
+            }
+        }
 
-/// When present in a request, this feature causes response data to be reported
-/// for 168 hours into the future instead of 48 hours.
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
-pub enum ExtendBy {
-    #[serde(rename = "hourly")]
-    Hourly
-}
+        api_response
+    }
 
-/// Model object representing language.
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
-#[serde(remote = "Lang")]
-pub enum Lang {
-    #[serde(rename = "ar")]
-    Arabic,
+    // Turns a non-success response into `ForecastError::Api`, with the details
+    // from its body.
+    async fn error_for_status(&self, response: Response) -> Result<Response, ForecastError> {
+        let status = response.status();
 
-    #[serde(rename = "az")]
-    Azerbaijani,
+        if status.is_success() {
+            return Ok(response);
+        }
 
-    #[serde(rename = "be")]
-    Belarusian,
+        let body = self.read_body(response).await?;
 
-    #[serde(rename = "bg")]
-    Bulgarian,
+        Err(ForecastError::Api(ApiError::from_body(status, &body)))
+    }
 
-    #[serde(rename = "bs")]
-    Bosnian,
+    // Reads the whole body, giving up as soon as it's known to be larger than
+    // `max_response_bytes`, going by `Content-Length` or what's been read so
+    // far.
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn read_body(&self, mut response: Response) -> Result<Vec<u8>, ForecastError> {
+        let limit = self.max_response_bytes.unwrap_or(usize::MAX);
 
-    #[serde(rename = "ca")]
-    Catalan,
+        if response.content_length().is_some_and(|length| length > limit as u64) {
+            return Err(ForecastError::ResponseTooLarge(limit));
+        }
 
-    #[serde(rename = "cz")]
-    Czech,
+        let mut body = Vec::new();
 
-    #[serde(rename = "da")]
-    Danish,
+        while let Some(chunk) = response.chunk().await? {
+            if chunk.len() > limit - body.len() {
+                return Err(ForecastError::ResponseTooLarge(limit));
+            }
 
-    #[serde(rename = "de")]
-    German,
+            body.extend_from_slice(&chunk);
+        }
 
-    #[serde(rename = "el")]
-    Greek,
+        Ok(body)
+    }
 
-    #[serde(rename = "en")]
-    English,
+    #[cfg(target_arch = "wasm32")]
+    async fn read_body(&self, response: Response) -> Result<Vec<u8>, ForecastError> {
+        let body = response.bytes().await?;
 
-    #[serde(rename = "es")]
-    Spanish,
+        match self.max_response_bytes {
+            Some(limit) if body.len() > limit => Err(ForecastError::ResponseTooLarge(limit)),
+            _ => Ok(body.to_vec())
+        }
+    }
+}
 
-    #[serde(rename = "et")]
-    Estonian,
+/// Builder object used to construct an ApiClient with default request
+/// parameters.
+#[derive(Debug)]
+pub struct ApiClientBuilder<'a, H: HttpTransport = Client> {
+    client: &'a H,
+    units: Option<Units>,
+    lang: Option<Lang>,
+    user_agent: HeaderValue,
+    #[cfg(feature = "json")]
+    max_response_bytes: Option<usize>
+}
 
-    #[serde(rename = "fi")]
-    Finnish,
+impl<'a, H: HttpTransport> ApiClientBuilder<'a, H> {
+    /// An ApiClient is constructed with the required `client`.
+    pub fn new(client: &'a H) -> ApiClientBuilder<'a, H> {
+        ApiClientBuilder {
+            client,
+            units: None,
+            lang: None,
+            user_agent: HeaderValue::from_static(DEFAULT_USER_AGENT),
+            #[cfg(feature = "json")]
+            max_response_bytes: None
+        }
+    }
 
-    #[serde(rename = "fr")]
-    French,
+    /// Set the measurement units used by requests built with
+    /// `ApiClient::forecast_request` and `ApiClient::time_machine_request`.
+    pub fn default_units(mut self, units: Units) -> ApiClientBuilder<'a, H> {
+        self.units = Some(units);
+        self
+    }
 
-    #[serde(rename = "hr")]
-    Croatian,
+    /// Set the language used by requests built with
+    /// `ApiClient::forecast_request` and `ApiClient::time_machine_request`.
+    pub fn default_lang(mut self, lang: Lang) -> ApiClientBuilder<'a, H> {
+        self.lang = Some(lang);
+        self
+    }
 
-    #[serde(rename = "hu")]
-    Hungarian,
+    /// Set the `User-Agent` sent with every request, in place of the default
+    /// `forecast-rs/{version}`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `user_agent` contains characters which aren't allowed in an
+    /// HTTP header value, such as newlines.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> ApiClientBuilder<'a, H> {
+        self.user_agent = HeaderValue::from_str(&user_agent.into())
+            .expect("user agent is not a valid header value");
+        self
+    }
 
-    #[serde(rename = "id")]
-    Indonesian,
+    /// Cap the size of the response bodies the client's deserializing
+    /// methods will read, e.g. when talking to the API through an untrusted
+    /// proxy. Reading stops with `ForecastError::ResponseTooLarge` as soon as
+    /// a body exceeds `max_response_bytes`. By default there is no limit.
+    ///
+    /// On WASM the limit is checked once the whole body has been read.
+    #[cfg(feature = "json")]
+    pub fn max_response_bytes(mut self, max_response_bytes: usize) -> ApiClientBuilder<'a, H> {
+        self.max_response_bytes = Some(max_response_bytes);
+        self
+    }
 
-    #[serde(rename = "is")]
-    Icelandic,
+    /// Finalize the client.
+    pub fn build(self) -> ApiClient<'a, H> {
+        ApiClient {
+            client: self.client,
+            units: self.units,
+            lang: self.lang,
+            user_agent: self.user_agent,
+            #[cfg(feature = "json")]
+            max_response_bytes: self.max_response_bytes
+        }
+    }
+}
 
-    #[serde(rename = "it")]
-    Italian,
+/// A deserialized response along with the `Lang` it was requested in, which
+/// the API doesn't report itself. Returned by `ApiClient::get_forecast_parsed`
+/// and `ApiClient::get_time_machine_parsed`.
+#[cfg(feature = "json")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedResponse {
+    pub response: ApiResponse,
 
-    #[serde(rename = "ja")]
-    Japanese,
+    /// The request's `lang`, or `None` if it had none.
+    pub requested_lang: Option<Lang>
+}
 
-    #[serde(rename = "ka")]
-    Georgian,
+/// The result of `ApiClient::get_forecast_conditional`.
+#[cfg(feature = "json")]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConditionalResponse {
+    /// The forecast hasn't changed since the given date.
+    NotModified,
 
-    #[serde(rename = "ko")]
-    Korean,
+    /// The forecast, along with the date it was last modified, if the API
+    /// sent one.
+    Modified(Box<ApiResponse>, Option<HttpDate>)
+}
 
-    #[serde(rename = "kw")]
-    Cornish,
+/// Freshness information parsed from the `Cache-Control` and `Expires` headers
+/// of an API response.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CacheInfo {
+    /// The `max-age` directive of the `Cache-Control` header.
+    pub max_age: Option<Duration>,
 
-    // can also be deserialized from "no", but will always be serialized to "nb"
-    #[serde(rename = "nb")]
-    NorwegianBokmal,
+    /// The `Expires` header, in UNIX seconds.
+    pub expires: Option<u64>
+}
 
-    #[serde(rename = "nl")]
-    Dutch,
-
-    #[serde(rename = "pl")]
-    Polish,
+impl CacheInfo {
+    /// Parse caching information from response headers. Headers which are
+    /// missing or malformed are left as `None`.
+    pub fn from_headers(headers: &HeaderMap) -> CacheInfo {
+        let max_age = headers.get(CACHE_CONTROL)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| {
+                value.split(',')
+                    .filter_map(|directive| directive.trim().strip_prefix("max-age="))
+                    .find_map(|seconds| seconds.trim_matches('"').parse().ok())
+            })
+            .map(Duration::from_secs);
+
+        let expires = headers.get(EXPIRES)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| httpdate::parse_http_date(value).ok())
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| duration.as_secs());
+
+        CacheInfo { max_age, expires }
+    }
 
-    #[serde(rename = "pt")]
-    Portuguese,
+    /// How long the response remains fresh, measured from `now` (UNIX
+    /// seconds). `max_age` takes precedence over `expires` when both are
+    /// present; an `expires` in the past yields a zero duration.
+    pub fn time_to_live(&self, now: u64) -> Option<Duration> {
+        match (self.max_age, self.expires) {
+            (Some(max_age), _) => Some(max_age),
+            (None, Some(expires)) => Some(Duration::from_secs(expires.saturating_sub(now))),
+            (None, None) => None
+        }
+    }
+}
 
-    #[serde(rename = "ro")]
-    Romanian,
+/// The CachingApiClient wraps an `ApiClient`, remembering the last
+/// deserialized response for each request URL and returning it for repeated
+/// requests until `ttl` has elapsed.
+///
+/// Responses are keyed on the full request URL with the API key removed, so
+/// cache keys never contain secrets. Unlike `ForecastRequest::cache_key`, the
+/// coordinates aren't rounded, so nearby locations are cached separately.
+#[cfg(all(feature = "json", not(target_arch = "wasm32")))]
+#[derive(Debug)]
+pub struct CachingApiClient<'a, H: HttpTransport = Client> {
+    client: ApiClient<'a, H>,
+    ttl: Duration,
+    cache: Mutex<HashMap<String, (Instant, ApiResponse)>>
+}
 
-    #[serde(rename = "ru")]
-    Russian,
+#[cfg(all(feature = "json", not(target_arch = "wasm32")))]
+impl<'a, H: HttpTransport> CachingApiClient<'a, H> {
+    /// Construct a new CachingApiClient whose entries live for `ttl`.
+    pub fn new(client: &'a H, ttl: Duration) -> CachingApiClient<'a, H> {
+        CachingApiClient::with_client(ApiClient::new(client), ttl)
+    }
 
-    #[serde(rename = "sk")]
-    Slovak,
+    /// Construct a new CachingApiClient wrapping an already configured
+    /// `ApiClient`, e.g. one built with `ApiClientBuilder`, whose entries
+    /// live for `ttl`.
+    pub fn with_client(client: ApiClient<'a, H>, ttl: Duration) -> CachingApiClient<'a, H> {
+        CachingApiClient {
+            client,
+            ttl,
+            cache: Mutex::new(HashMap::new())
+        }
+    }
 
-    #[serde(rename = "sl")]
-    Slovenian,
+    /// The time-to-live of cached responses.
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
 
-    #[serde(rename = "sr")]
-    Serbian,
+    /// Send a [Forecast API](https://darksky.net/dev/docs/forecast)
+    /// request, or return a cached response for the same URL if one is
+    /// younger than the TTL.
+    ///
+    /// # Errors
+    ///
+    /// On a cache miss this fails under the same conditions as
+    /// `ApiClient::get_forecasts`. Failed requests are not cached.
+    pub async fn get_forecast<'b, T>(&self, request: T) -> Result<ApiResponse, ForecastError>
+        where T : Borrow<ForecastRequest<'b>> + Sized {
+        self.get_cached(request.borrow().target()).await
+    }
 
-    #[serde(rename = "sv")]
-    Swedish,
+    /// Send a [Time Machine
+    /// API](https://darksky.net/dev/docs/time-machine) request, or return a
+    /// cached response for the same URL if one is younger than the TTL.
+    ///
+    /// # Errors
+    ///
+    /// On a cache miss this fails under the same conditions as
+    /// `ApiClient::get_forecasts`. Failed requests are not cached.
+    pub async fn get_time_machine<'b, T>(&self, request: T) -> Result<ApiResponse, ForecastError>
+        where T : Borrow<TimeMachineRequest<'b>> + Sized {
+        self.get_cached(request.borrow().target()).await
+    }
 
-    #[serde(rename = "tet")]
-    Tetum,
+    /// Evict every cached response.
+    pub fn clear(&self) {
+        self.cache.lock().unwrap().clear();
+    }
 
-    #[serde(rename = "tr")]
-    Turkish,
+    async fn get_cached(&self, target: RequestTarget<'_>) -> Result<ApiResponse, ForecastError> {
+        let key = url_without_api_key(target.api_key, target.url);
 
-    #[serde(rename = "uk")]
-    Ukranian,
+        if let Some(response) = self.lookup(&key) {
+            return Ok(response);
+        }
 
-    #[serde(rename = "x-pig-latin")]
-    IgpayAtinlay,
+        let response = self.client.get_api_response(target).await?;
 
-    #[serde(rename = "zh")]
-    SimplifiedChinese,
+        self.cache.lock().unwrap().insert(key, (Instant::now(), response.clone()));
 
-    #[serde(rename = "zh-tw")]
-    TraditionalChinese
-}
+        Ok(response)
+    }
 
-// This is needed to take into account the aliasing of "nb" as "no".
-// See https://github.com/serde-rs/serde/issues/1174#issuecomment-372411280
-impl <'de> Deserialize<'de> for Lang {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-        where D: Deserializer<'de>
-    {
-        let value = String::deserialize(deserializer)?;
+    fn lookup(&self, key: &str) -> Option<ApiResponse> {
+        let mut cache = self.cache.lock().unwrap();
 
-        if value == "no" {
-            Ok(Lang::NorwegianBokmal)
-        } else {
-            Lang::deserialize(value.into_deserializer())
+        match cache.get(key) {
+            Some((inserted, response)) if inserted.elapsed() < self.ttl => Some(response.clone()),
+            Some(_) => {
+                cache.remove(key);
+                None
+            },
+            None => None
         }
     }
 }
 
-// This is needed to take into account the aliasing of "nb" as "no".
-// See https://github.com/serde-rs/serde/issues/1174#issuecomment-372411280
-impl Serialize for Lang {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-        where S: Serializer
-    {
-        Lang::serialize(&self, serializer)
-    }
-}
+/// A place found by a `Geocoder`.
+#[cfg(feature = "geocode")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Place {
+    /// The place's full name, e.g. `"Berlin, Germany"`.
+    pub name: String,
 
-/// Model object representing measurement units.
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
-pub enum Units {
-    #[serde(rename = "auto")]
-    Auto,
+    pub latitude: f64,
 
-    #[serde(rename = "ca")]
-    CA,
+    pub longitude: f64
+}
 
-    #[serde(rename = "uk2")]
-    UK,
+/// The future returned by `Geocoder::geocode`.
+#[cfg(all(feature = "geocode", not(target_arch = "wasm32")))]
+pub type GeocodeFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Vec<Place>, Box<dyn Error + Send + Sync>>> + Send + 'a>>;
+
+/// The future returned by `Geocoder::geocode`. As with `TransportFuture`, it
+/// isn't `Send` on WASM.
+#[cfg(all(feature = "geocode", target_arch = "wasm32"))]
+pub type GeocodeFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Vec<Place>, Box<dyn Error + Send + Sync>>> + 'a>>;
+
+/// The Geocoder resolves place names for `ForecastRequestBuilder::from_place`.
+/// This crate doesn't provide one: implement it for a client of Nominatim or
+/// another geocoding service.
+#[cfg(feature = "geocode")]
+pub trait Geocoder {
+    /// Find the places matching `name`, e.g. `"Berlin"`, with the best match
+    /// first.
+    fn geocode<'a>(&'a self, name: &'a str) -> GeocodeFuture<'a>;
+}
 
-    #[serde(rename = "us")]
-    Imperial,
+/// The future returned by `WeatherProvider` methods.
+#[cfg(all(feature = "json", not(target_arch = "wasm32")))]
+pub type ProviderFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<ApiResponse, ForecastError>> + Send + 'a>>;
+
+/// The future returned by `WeatherProvider` methods. As with
+/// `TransportFuture`, it isn't `Send` on WASM.
+#[cfg(all(feature = "json", target_arch = "wasm32"))]
+pub type ProviderFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<ApiResponse, ForecastError>> + 'a>>;
+
+/// A source of deserialized forecasts, implemented by `ApiClient` and
+/// `CachingApiClient`. Being object safe, it can be held as a
+/// `Box<dyn WeatherProvider>` and swapped for a test double.
+#[cfg(feature = "json")]
+pub trait WeatherProvider {
+    /// Send a [Forecast API](https://darksky.net/dev/docs/forecast)
+    /// request and deserialize the response.
+    fn get_forecast<'a>(&'a self, request: &'a ForecastRequest<'_>) -> ProviderFuture<'a>;
 
-    #[serde(rename = "si")]
-    SI
+    /// Send a [Time Machine
+    /// API](https://darksky.net/dev/docs/time-machine) request and deserialize
+    /// the response.
+    fn get_time_machine<'a>(&'a self, request: &'a TimeMachineRequest<'_>) -> ProviderFuture<'a>;
 }
 
-/// Model object representing an Alert's severity.
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
-pub enum Severity {
-    #[serde(rename = "advisory")]
-    Advisory,
-
-    #[serde(rename = "watch")]
-    Watch,
+// `ApiClient`'s own `get_forecast` and `get_time_machine` return the raw
+// `Response`, and take precedence in method calls on an `ApiClient`.
+#[cfg(feature = "json")]
+impl<'c, H: HttpTransport + Sync> WeatherProvider for ApiClient<'c, H> {
+    fn get_forecast<'a>(&'a self, request: &'a ForecastRequest<'_>) -> ProviderFuture<'a> {
+        Box::pin(self.get_api_response(request.target()))
+    }
 
-    #[serde(rename = "warning")]
-    Warning
+    fn get_time_machine<'a>(&'a self, request: &'a TimeMachineRequest<'_>) -> ProviderFuture<'a> {
+        Box::pin(self.get_api_response(request.target()))
+    }
 }
 
-/// Model object containing various properties, each representing the average
-/// (unless otherwise specified) of a particular weather phenomenon occurring
-/// during a period of time.
-#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
-pub struct DataPoint {
-    #[serde(rename = "apparentTemperature")]
-    pub apparent_temperature: Option<f64>,
-
-    #[serde(rename = "apparentTemperatureHigh")]
-    pub apparent_temperature_high: Option<f64>,
+#[cfg(all(feature = "json", not(target_arch = "wasm32")))]
+impl<'c, H: HttpTransport + Sync> WeatherProvider for CachingApiClient<'c, H> {
+    fn get_forecast<'a>(&'a self, request: &'a ForecastRequest<'_>) -> ProviderFuture<'a> {
+        Box::pin(self.get_cached(request.target()))
+    }
 
-    #[serde(rename = "apparentTemperatureHighTime")]
-    pub apparent_temperature_high_time: Option<u64>,
+    fn get_time_machine<'a>(&'a self, request: &'a TimeMachineRequest<'_>) -> ProviderFuture<'a> {
+        Box::pin(self.get_cached(request.target()))
+    }
+}
 
-    #[serde(rename = "apparentTemperatureLow")]
-    pub apparent_temperature_low: Option<f64>,
+// The API endpoint a request is sent to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Endpoint {
+    Forecast,
+    TimeMachine
+}
 
-    #[serde(rename = "apparentTemperatureLowTime")]
-    pub apparent_temperature_low_time: Option<u64>,
+impl Endpoint {
+    #[cfg_attr(not(any(feature = "tracing", feature = "metrics")), allow(dead_code))]
+    fn as_str(self) -> &'static str {
+        match self {
+            Endpoint::Forecast => "forecast",
+            Endpoint::TimeMachine => "time_machine"
+        }
+    }
+}
 
-    #[deprecated(since = "1.0.0")]
-    #[serde(rename = "apparentTemperatureMax")]
-    pub apparent_temperature_max: Option<f64>,
+// The parts of a ForecastRequest or TimeMachineRequest needed to send it and
+// report on it.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(not(feature = "tracing"), allow(dead_code))]
+struct RequestTarget<'r> {
+    endpoint: Endpoint,
+    api_key: &'r str,
+    latitude: f64,
+    longitude: f64,
+    url: &'r Url
+}
 
-    #[deprecated(since = "1.0.0")]
-    #[serde(rename = "apparentTemperatureMaxTime")]
-    pub apparent_temperature_max_time: Option<u64>,
+#[cfg(feature = "json")]
+impl<'r> RequestTarget<'r> {
+    // Recover what we can from a URL which wasn't built by this crate,
+    // expecting `.../forecast/{api_key}/{latitude},{longitude}[,{time}]`. The
+    // coordinates are NaN and the API key empty if it has another shape.
+    fn from_url(url: &'r Url) -> RequestTarget<'r> {
+        let segments: Vec<&str> = url.path_segments().map(Iterator::collect).unwrap_or_default();
+
+        let (api_key, location) = match *segments.as_slice() {
+            [.., api_key, location] => (api_key, location),
+            _ => ("", "")
+        };
 
-    #[deprecated(since = "1.0.0")]
-    #[serde(rename = "apparentTemperatureMin")]
-    pub apparent_temperature_min: Option<f64>,
+        let mut parts = location.split(',').map(|part| part.parse().ok());
+
+        match (parts.next().flatten(), parts.next().flatten(), parts.next()) {
+            (Some(latitude), Some(longitude), time) => RequestTarget {
+                endpoint: if time.is_some() { Endpoint::TimeMachine } else { Endpoint::Forecast },
+                api_key,
+                latitude,
+                longitude,
+                url
+            },
+            _ => RequestTarget {
+                endpoint: Endpoint::Forecast,
+                api_key: "",
+                latitude: f64::NAN,
+                longitude: f64::NAN,
+                url
+            }
+        }
+    }
+}
 
-    #[deprecated(since = "1.0.0")]
-    #[serde(rename = "apparentTemperatureMinTime")]
-    pub apparent_temperature_min_time: Option<u64>,
+// Count and time a request to `endpoint`, counting it as an error if it failed
+// or the API responded with a non-success status. A `304 Not Modified`, the
+// expected outcome of a conditional request, is not an error.
+#[cfg(feature = "metrics")]
+fn record_metrics(endpoint: Endpoint, result: &ApiResult<Response>, elapsed: Duration) {
+    metrics::counter!("forecast_requests_total", "endpoint" => endpoint.as_str()).increment(1);
+    metrics::histogram!("forecast_request_duration_seconds", "endpoint" => endpoint.as_str())
+        .record(elapsed.as_secs_f64());
+
+    match *result {
+        Ok(ref response) if response.status() == StatusCode::NOT_MODIFIED => (),
+        Ok(ref response) if !response.status().is_success() => record_error(endpoint, "status"),
+        Ok(_) => (),
+        Err(_) => record_error(endpoint, "http")
+    }
+}
 
-    #[serde(rename = "cloudCover")]
-    pub cloud_cover: Option<f64>,
+// Count a failed request to `endpoint` by the `kind` of failure: `"http"` if
+// it couldn't be sent, `"status"` for a non-success response, or `"json"` if
+// the response couldn't be deserialized.
+#[cfg(feature = "metrics")]
+fn record_error(endpoint: Endpoint, kind: &'static str) {
+    metrics::counter!(
+        "forecast_request_errors_total",
+        "endpoint" => endpoint.as_str(),
+        "kind" => kind
+    ).increment(1);
+}
 
-    #[serde(rename = "dewPoint")]
-    pub dew_point: Option<f64>,
+// Render `url` with the path segment holding `api_key` replaced, so that it
+// can be logged.
+#[cfg(feature = "tracing")]
+fn redacted_url(api_key: &str, url: &Url) -> String {
+    let mut redacted = url.clone();
 
-    pub humidity: Option<f64>,
+    if !api_key.is_empty() {
+        let segments: Vec<&str> = url.path_segments()
+            .map(|segments| {
+                segments.map(|s| if s == api_key { "REDACTED" } else { s }).collect()
+            })
+            .unwrap_or_default();
 
-    pub icon: Option<Icon>,
+        redacted.set_path(&segments.join("/"));
+    }
 
-    #[serde(rename = "moonPhase")]
-    pub moon_phase: Option<f64>,
+    redacted.into()
+}
 
-    #[serde(rename = "nearestStormBearing")]
-    pub nearest_storm_bearing: Option<f64>,
+// Render `url` without the path segment holding `api_key`.
+fn url_without_api_key(api_key: &str, url: &Url) -> String {
+    let mut key = url.clone();
 
-    #[serde(rename = "nearestStormDistance")]
-    pub nearest_storm_distance: Option<f64>,
+    let segments: Vec<String> = url.path_segments()
+        .map(|segments| segments.filter(|s| *s != api_key).map(String::from).collect())
+        .unwrap_or_default();
 
-    pub ozone: Option<f64>,
+    key.set_path(&segments.join("/"));
 
-    #[serde(rename = "precipAccumulation")]
-    pub precip_accumulation: Option<f64>,
+    key.into()
+}
 
-    #[serde(rename = "precipIntensity")]
-    pub precip_intensity: Option<f64>,
+// The `lang` query parameter of `url`, if it is a recognized language.
+#[cfg(feature = "json")]
+fn requested_lang(url: &Url) -> Option<Lang> {
+    url.query_pairs()
+        .find(|(key, _)| key == LANG)
+        .and_then(|(_, value)| value.parse().ok())
+}
 
-    #[serde(rename = "precipIntensityMax")]
-    pub precip_intensity_max: Option<f64>,
+// Incrementally picks the data points of the `hourly` block out of a response
+// body which arrives in arbitrarily sized chunks, buffering only the bytes of
+// the data point currently being read.
+#[cfg(all(feature = "json", not(target_arch = "wasm32")))]
+struct HourlyDataScanner {
+    stack: Vec<JsonContainer>,
+    in_string: bool,
+    escaped: bool,
+    reading_key: bool,
+    key: Vec<u8>,
+    point: Vec<u8>,
+    point_depth: Option<usize>
+}
 
-    #[serde(rename = "precipIntensityMaxTime")]
-    pub precip_intensity_max_time: Option<u64>,
+// An object, along with the key of the value currently being read, or an array.
+#[cfg(all(feature = "json", not(target_arch = "wasm32")))]
+enum JsonContainer {
+    Object { key: Option<Vec<u8>>, expecting_key: bool },
+    Array
+}
 
-    #[serde(rename = "precipProbability")]
-    pub precip_probability: Option<f64>,
+#[cfg(all(feature = "json", not(target_arch = "wasm32")))]
+impl HourlyDataScanner {
+    fn new() -> HourlyDataScanner {
+        HourlyDataScanner {
+            stack: Vec::new(),
+            in_string: false,
+            escaped: false,
+            reading_key: false,
+            key: Vec::new(),
+            point: Vec::new(),
+            point_depth: None
+        }
+    }
 
-    #[serde(rename = "precipType")]
-    pub precip_type: Option<PrecipType>,
+    fn feed(&mut self, chunk: &[u8]) -> Vec<Result<DataPoint, ForecastError>> {
+        let mut data_points = Vec::new();
 
-    pub pressure: Option<f64>,
+        for &byte in chunk {
+            if self.point_depth.is_some() {
+                self.point.push(byte);
+            }
 
-    pub summary: Option<String>,
+            if self.in_string {
+                if self.escaped {
+                    self.escaped = false;
+                } else if byte == b'\\' {
+                    self.escaped = true;
+                } else if byte == b'"' {
+                    self.in_string = false;
 
-    #[serde(rename = "sunriseTime")]
-    pub sunrise_time: Option<u64>,
+                    if self.reading_key {
+                        self.reading_key = false;
 
-    #[serde(rename = "sunsetTime")]
-    pub sunset_time: Option<u64>,
+                        let key = self.key.clone();
 
-    pub temperature: Option<f64>,
+                        if let Some((current_key, _)) = self.current_object() {
+                            *current_key = Some(key);
+                        }
+                    }
+                } else if self.reading_key {
+                    self.key.push(byte);
+                }
 
-    #[serde(rename = "temperatureHigh")]
-    pub temperature_high: Option<f64>,
+                continue;
+            }
 
-    #[serde(rename = "temperatureHighTime")]
-    pub temperature_high_time: Option<u64>,
+            match byte {
+                b'"' => {
+                    self.in_string = true;
+
+                    if let Some((_, true)) = self.current_object() {
+                        self.reading_key = true;
+                        self.key.clear();
+                    }
+                },
+                b'{' => {
+                    if self.point_depth.is_none() && self.in_hourly_data() {
+                        self.point_depth = Some(self.stack.len());
+                        self.point.clear();
+                        self.point.push(byte);
+                    }
+
+                    self.stack.push(JsonContainer::Object { key: None, expecting_key: true });
+                },
+                b'[' => self.stack.push(JsonContainer::Array),
+                b'}' | b']' => {
+                    self.stack.pop();
+
+                    if self.point_depth == Some(self.stack.len()) {
+                        self.point_depth = None;
+                        data_points.push(
+                            serde_json::from_slice(&self.point).map_err(ForecastError::from)
+                        );
+                    }
+                },
+                b':' => {
+                    if let Some((_, expecting_key)) = self.current_object() {
+                        *expecting_key = false;
+                    }
+                },
+                b',' => {
+                    if let Some((key, expecting_key)) = self.current_object() {
+                        *key = None;
+                        *expecting_key = true;
+                    }
+                },
+                _ => ()
+            }
+        }
 
-    #[serde(rename = "temperatureLow")]
-    pub temperature_low: Option<f64>,
+        data_points
+    }
 
-    #[serde(rename = "temperatureLowTime")]
-    pub temperature_low_time: Option<u64>,
+    // The key and key-expectation state of the innermost container, if it is an
+    // object.
+    fn current_object(&mut self) -> Option<(&mut Option<Vec<u8>>, &mut bool)> {
+        match self.stack.last_mut() {
+            Some(JsonContainer::Object { ref mut key, ref mut expecting_key }) => {
+                Some((key, expecting_key))
+            },
+            _ => None
+        }
+    }
 
-    #[deprecated(since = "1.0.0")]
-    #[serde(rename = "temperatureMax")]
-    pub temperature_max: Option<f64>,
+    // Whether the scanner is directly inside the `hourly.data` array.
+    fn in_hourly_data(&self) -> bool {
+        let is_object_at = |container: &JsonContainer, name: &[u8]| match *container {
+            JsonContainer::Object { key: Some(ref key), .. } => key.as_slice() == name,
+            _ => false
+        };
 
-    #[deprecated(since = "1.0.0")]
-    #[serde(rename = "temperatureMaxTime")]
-    pub temperature_max_time: Option<u64>,
+        match self.stack.as_slice() {
+            [root, hourly, JsonContainer::Array] => {
+                is_object_at(root, b"hourly") && is_object_at(hourly, b"data")
+            },
+            _ => false
+        }
+    }
+}
 
-    #[deprecated(since = "1.0.0")]
-    #[serde(rename = "temperatureMin")]
-    pub temperature_min: Option<f64>,
+// request model objects and their builders
 
-    #[deprecated(since = "1.0.0")]
-    #[serde(rename = "temperatureMinTime")]
-    pub temperature_min_time: Option<u64>,
+/// Model object representing a request to the Forecast API.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForecastRequest<'a> {
+    api_key: Cow<'a, str>,
+    latitude: f64,
+    longitude: f64,
+    url: Url,
+    exclude: Vec<ExcludeBlock>,
+    extend: Option<ExtendBy>,
+    lang: Option<Lang>,
+    units: Option<Units>
+}
 
-    pub time: u64,
+impl<'a> ForecastRequest<'a> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        api_key: impl Into<Cow<'a, str>>,
+        latitude: f64,
+        longitude: f64,
+        url: Url,
+        exclude: Vec<ExcludeBlock>,
+        extend: Option<ExtendBy>,
+        lang: Option<Lang>,
+        units: Option<Units>
+    ) -> ForecastRequest<'a> {
+        ForecastRequest {
+            api_key: api_key.into(),
+            latitude,
+            longitude,
+            url,
+            exclude,
+            extend,
+            lang,
+            units
+        }
+    }
 
-    #[serde(rename = "uvIndex")]
-    pub uv_index: Option<f64>,
+    /// The API key the request is authenticated with.
+    pub fn api_key(&self) -> &str {
+        &self.api_key
+    }
 
-    #[serde(rename = "uvIndexTime")]
-    pub uv_index_time: Option<u64>,
+    /// The requested latitude.
+    pub fn latitude(&self) -> f64 {
+        self.latitude
+    }
 
-    pub visibility: Option<f64>,
+    /// The requested longitude.
+    pub fn longitude(&self) -> f64 {
+        self.longitude
+    }
 
-    #[serde(rename = "windBearing")]
-    pub wind_bearing: Option<f64>,
+    /// The DataBlocks excluded from the response.
+    pub fn exclude(&self) -> &[ExcludeBlock] {
+        &self.exclude
+    }
 
-    #[serde(rename = "windGust")]
-    pub wind_gust: Option<f64>,
+    /// The DataBlock extended in the response, if any.
+    pub fn extend(&self) -> Option<&ExtendBy> {
+        self.extend.as_ref()
+    }
 
-    #[serde(rename = "windGustTime")]
-    pub wind_gust_time: Option<u64>,
+    /// The requested language, if any.
+    pub fn lang(&self) -> Option<&Lang> {
+        self.lang.as_ref()
+    }
 
-    #[serde(rename = "windSpeed")]
-    pub wind_speed: Option<f64>
-}
+    /// The requested measurement units, if any.
+    pub fn units(&self) -> Option<&Units> {
+        self.units.as_ref()
+    }
 
-/// Model object representing the various weather phenomena occurring over a
-/// period of time.
-#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
-pub struct DataBlock {
-    pub data: Vec<DataPoint>,
+    /// The URL this request will be sent to. Note that it contains the API
+    /// key.
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
 
-    pub summary: Option<String>,
+    /// A reqwest `RequestBuilder` for this request, for sending it through
+    /// your own HTTP stack rather than an `ApiClient`, e.g. to add headers or
+    /// middleware first.
+    pub fn to_reqwest(&self, client: &Client) -> RequestBuilder {
+        client.get(self.url.clone())
+    }
 
-    pub icon: Option<Icon>
-}
+    /// A stable key for this request which doesn't contain the API key, e.g.
+    /// `"42.3736,-71.1097?units=si"`, for deduping or caching requests. The
+    /// coordinates are rounded to 4 decimal places (about 11 m), so requests
+    /// for locations closer than that share a key. The parameters follow in
+    /// the order they appear in the URL, including extra query parameters.
+    ///
+    /// This is for your own deduping or caching: the `CachingApiClient` doesn't
+    /// use it, and keys its responses on the full request URL instead.
+    pub fn cache_key(&self) -> String {
+        let round = |degrees: f64| (degrees * 10_000.0).round() / 10_000.0 + 0.0;
 
-/// Model object representing a severe weather warning issued by a government
-/// authority for the requested location.
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
-pub struct Alert {
-    pub description: String,
+        let mut key = format!("{:.4},{:.4}", round(self.latitude), round(self.longitude));
 
-    pub expires: u64,
+        if let Some(query) = self.url.query().filter(|query| !query.is_empty()) {
+            key.push('?');
+            key.push_str(query);
+        }
 
-    pub regions: Vec<String>,
+        key
+    }
 
-    pub severity: Severity,
+    /// Returns true if `other` requests the same location with the same
+    /// parameters, ignoring the API key, e.g. to dedupe requests made with
+    /// several rotating keys.
+    pub fn same_location_and_params(&self, other: &ForecastRequest) -> bool {
+        self.latitude == other.latitude &&
+            self.longitude == other.longitude &&
+            self.exclude == other.exclude &&
+            self.extend == other.extend &&
+            self.lang == other.lang &&
+            self.units == other.units &&
+            url_without_api_key(&self.api_key, &self.url) ==
+                url_without_api_key(&other.api_key, &other.url)
+    }
 
-    pub time: u64,
-
-    pub title: String,
+    fn target(&self) -> RequestTarget<'_> {
+        RequestTarget {
+            endpoint: Endpoint::Forecast,
+            api_key: &self.api_key,
+            latitude: self.latitude,
+            longitude: self.longitude,
+            url: &self.url
+        }
+    }
+}
 
-    pub uri: String
+/// Builder object used to construct a ForecastRequest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForecastRequestBuilder<'a> {
+    api_key: Cow<'a, str>,
+    latitude: f64,
+    longitude: f64,
+    exclude: ExcludeSet,
+    extend: Option<ExtendBy>,
+    lang: Option<Lang>,
+    units: Option<Units>,
+    version: Option<u8>,
+    query_params: Vec<(String, String)>,
+    coordinate_precision: usize
 }
 
-/// Model object representing a flag which contains miscellaneous metadata about
-/// a request.
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
-pub struct Flags {
-    #[serde(rename = "darksky-unavailable")]
-    pub darksky_unavailable: Option<String>,
+impl<'a> ForecastRequestBuilder<'a> {
+    /// A Forecast API request is constructed with required params
+    /// `api_key`, `latitude`, and `longitude`. The `api_key` may be either
+    /// borrowed or owned, e.g. a `&str` or a `String`.
+    pub fn new(
+        api_key: impl Into<Cow<'a, str>>,
+        latitude: f64,
+        longitude: f64
+    ) -> ForecastRequestBuilder<'a> {
+        ForecastRequestBuilder {
+            api_key: api_key.into(),
+            latitude,
+            longitude,
+            exclude: ExcludeSet::new(),
+            extend: None,
+            lang: None,
+            units: None,
+            version: None,
+            query_params: Vec::new(),
+            coordinate_precision: DEFAULT_COORDINATE_PRECISION
+        }
+    }
 
-    pub sources: Vec<String>,
+    /// Construct a Forecast API request from a `geo::Point`. The point's `y`
+    /// coordinate is taken as the latitude and its `x` coordinate as the
+    /// longitude.
+    #[cfg(feature = "geo")]
+    pub fn from_point(
+        api_key: impl Into<Cow<'a, str>>,
+        point: geo::Point<f64>
+    ) -> ForecastRequestBuilder<'a> {
+        ForecastRequestBuilder::new(api_key, point.y(), point.x())
+    }
 
-    pub units: Units
-}
+    /// Construct a Forecast API request for the center of the area described
+    /// by a full Open Location Code (plus code), e.g. `"87JC9W76+HV"`. Returns
+    /// `ForecastError::InvalidRequest` if `code` is not a valid full code.
+    #[cfg(feature = "olc")]
+    pub fn from_plus_code(
+        api_key: impl Into<Cow<'a, str>>,
+        code: &str
+    ) -> Result<ForecastRequestBuilder<'a>, ForecastError> {
+        let area = open_location_code::decode(code).map_err(ForecastError::InvalidRequest)?;
+
+        Ok(ForecastRequestBuilder::new(
+            api_key,
+            (area.south + area.north) / 2.0,
+            (area.west + area.east) / 2.0
+        ))
+    }
 
-/// Model object representing a Forecast or Time Machine API response.
-#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
-pub struct ApiResponse {
-    pub latitude: f64,
+    /// A Forecast API request for the place called `name`, e.g. `"Berlin"`,
+    /// as resolved by `geocoder`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ForecastError::Geocode` if `geocoder` fails, finds no place
+    /// called `name`, or finds more than one, in which case they are all
+    /// returned so that the user can pick one and the request can be made
+    /// with its coordinates.
+    #[cfg(feature = "geocode")]
+    pub async fn from_place(
+        api_key: impl Into<Cow<'a, str>>,
+        name: &str,
+        geocoder: &impl Geocoder
+    ) -> Result<ForecastRequestBuilder<'a>, ForecastError> {
+        let places = geocoder.geocode(name).await.map_err(GeocodeError::Geocoder)?;
+
+        match places.as_slice() {
+            [place] => Ok(ForecastRequestBuilder::new(api_key, place.latitude, place.longitude)),
+            [] => Err(GeocodeError::NotFound(name.to_string()).into()),
+            _ => Err(GeocodeError::Ambiguous(name.to_string(), places).into())
+        }
+    }
 
-    pub longitude: f64,
+    /// A starting point for requests which share an `api_key`: no excluded
+    /// blocks, `Units::SI` and `Lang::English`. The coordinates default to
+    /// `0.0, 0.0` and should be set with `with_coordinates`.
+    pub fn with_defaults(api_key: impl Into<Cow<'a, str>>) -> ForecastRequestBuilder<'a> {
+        ForecastRequestBuilder::new(api_key, 0.0, 0.0)
+            .units(Units::SI)
+            .lang(Lang::English)
+    }
 
-    pub timezone: String,
+    /// Set the latitude and longitude of the request.
+    pub fn with_coordinates(mut self, latitude: f64, longitude: f64) -> ForecastRequestBuilder<'a> {
+        self.latitude = latitude;
+        self.longitude = longitude;
+        self
+    }
 
-    #[deprecated(since = "1.0.0")]
-    pub offset: i64,
+    /// Add a DataBlock to exclude from the response.
+    pub fn exclude_block(mut self, exclude_block: ExcludeBlock) -> ForecastRequestBuilder<'a> {
+        self.exclude.insert(exclude_block);
+        self
+    }
 
-    pub currently: Option<DataPoint>,
+    /// Add multiple DataBlocks to exclude from the response.
+    ///
+    /// Note that this drains `exclude_blocks`, leaving the caller's `Vec`
+    /// empty. Use `exclude_all` to keep it intact.
+    pub fn exclude_blocks(mut self, exclude_blocks: &mut Vec<ExcludeBlock>)
+        -> ForecastRequestBuilder<'a> {
+        self.exclude.extend(exclude_blocks.drain(..));
+        self
+    }
 
-    pub minutely: Option<DataBlock>,
+    /// Add multiple DataBlocks to exclude from the response, cloning them
+    /// from `exclude_blocks`.
+    pub fn exclude_all(mut self, exclude_blocks: &[ExcludeBlock]) -> ForecastRequestBuilder<'a> {
+        self.exclude.extend(exclude_blocks.iter().cloned());
+        self
+    }
 
-    pub hourly: Option<DataBlock>,
+    /// Request a lighter response by excluding the `minutely` and `flags`
+    /// DataBlocks, which many applications never use. This is in addition to
+    /// any other excluded blocks.
+    pub fn minimal(mut self) -> ForecastRequestBuilder<'a> {
+        self.exclude.insert(ExcludeBlock::Minutely);
+        self.exclude.insert(ExcludeBlock::Flags);
+        self
+    }
 
-    pub daily: Option<DataBlock>,
+    /// Exclude every DataBlock except `block` from the response, replacing
+    /// any previously excluded blocks.
+    pub fn only(self, block: ExcludeBlock) -> ForecastRequestBuilder<'a> {
+        self.only_blocks(&[block])
+    }
 
-    pub alerts: Option<Vec<Alert>>,
+    /// Exclude every DataBlock except `blocks` from the response, replacing
+    /// any previously excluded blocks.
+    pub fn only_blocks(mut self, blocks: &[ExcludeBlock]) -> ForecastRequestBuilder<'a> {
+        self.exclude = ExcludeBlock::all()
+            .iter()
+            .filter(|block| !blocks.contains(block))
+            .cloned()
+            .collect();
+        self
+    }
+
+    /// Exclude every DataBlock except `alerts` and `flags`, for watching for
+    /// severe weather without downloading the forecast itself. This replaces
+    /// any previously excluded blocks.
+    ///
+    /// The API accepts excluding `currently` along with everything else, so
+    /// the response is just the location, `alerts` and `flags`. Note that the
+    /// `alerts` property is omitted entirely when there are no alerts.
+    pub fn alerts_only(self) -> ForecastRequestBuilder<'a> {
+        self.only_blocks(&[ExcludeBlock::Alerts, ExcludeBlock::Flags])
+    }
+
+    /// Remove every previously excluded DataBlock.
+    pub fn clear_excludes(mut self) -> ForecastRequestBuilder<'a> {
+        self.exclude.clear();
+        self
+    }
+
+    /// Remove `block` from the excluded DataBlocks, if present.
+    pub fn remove_exclude(mut self, block: &ExcludeBlock) -> ForecastRequestBuilder<'a> {
+        self.exclude.remove(block);
+        self
+    }
+
+    /// Returns true if `block` is currently excluded.
+    pub fn is_excluded(&self, block: &ExcludeBlock) -> bool {
+        self.exclude.contains(block)
+    }
+
+    /// Extend the time window of the response data from 48 hours to
+    /// 168 hours.
+    pub fn extend(mut self, extend: ExtendBy) -> ForecastRequestBuilder<'a> {
+        self.extend = Some(extend);
+        self
+    }
+
+    /// Set the language for messages in the response data.
+    pub fn lang(mut self, lang: Lang) -> ForecastRequestBuilder<'a> {
+        self.lang = Some(lang);
+        self
+    }
+
+    /// Set the measurement units for response data.
+    pub fn units(mut self, units: Units) -> ForecastRequestBuilder<'a> {
+        self.units = Some(units);
+        self
+    }
+
+    /// Request a specific version of the Pirate Weather response format.
+    /// Version 2 adds the `fire_index`, `smoke`, `ice_accumulation`,
+    /// `liquid_accumulation` and `snow_accumulation` properties to data
+    /// points, along with additional `flags`. Without it the API responds
+    /// with version 1.
+    pub fn version(mut self, version: u8) -> ForecastRequestBuilder<'a> {
+        self.version = Some(version);
+        self
+    }
+
+    /// Add an arbitrary query parameter, e.g. an experimental one which this
+    /// crate doesn't model yet. These are appended after the modeled
+    /// parameters, and are percent-encoded as needed.
+    pub fn query_param(mut self, key: &str, value: &str) -> ForecastRequestBuilder<'a> {
+        self.query_params.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Set the number of decimal places the coordinates are formatted with
+    /// in the URL, in place of `DEFAULT_COORDINATE_PRECISION`.
+    pub fn coordinate_precision(mut self, precision: usize) -> ForecastRequestBuilder<'a> {
+        self.coordinate_precision = precision;
+        self
+    }
+
+    /// Finalize the request. Use `try_build` to have the request checked for
+    /// contradictory options first.
+    pub fn build(self) -> ForecastRequest<'a> {
+        let url = self.build_url();
+
+        ForecastRequest::new(
+            self.api_key,
+            self.latitude,
+            self.longitude,
+            url,
+            self.exclude.to_vec(),
+            self.extend,
+            self.lang,
+            self.units
+        )
+    }
+
+    /// Finalize the request, checking that it doesn't contain contradictory
+    /// options which the API would silently ignore.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ForecastError::InvalidRequest` if the request extends a block
+    /// which it also excludes.
+    pub fn try_build(self) -> Result<ForecastRequest<'a>, ForecastError> {
+        if let Some(ref extend) = self.extend {
+            let extended = match *extend {
+                ExtendBy::Hourly => ExcludeBlock::Hourly
+            };
+
+            if self.exclude.contains(&extended) {
+                return Err(ForecastError::InvalidRequest(format!(
+                    "cannot extend the {} block because it is excluded",
+                    extended.as_query_str()
+                )));
+            }
+        }
+
+        Ok(self.build())
+    }
+
+    /// Build a request for each `(latitude, longitude)` in `coordinates`,
+    /// sharing every other setting of this builder, e.g. for a grid of
+    /// locations. The results are in the same order as `coordinates`.
+    ///
+    /// # Errors
+    ///
+    /// Each request succeeds or fails independently. A request fails with
+    /// `ForecastError::InvalidRequest` if its latitude isn't within
+    /// `[-90, 90]` or its longitude within `[-180, 180]`, or under the same
+    /// conditions as `try_build`.
+    pub fn build_for_coords(
+        &self,
+        coordinates: &[(f64, f64)]
+    ) -> Vec<Result<ForecastRequest<'a>, ForecastError>> {
+        coordinates.iter()
+            .map(|&(latitude, longitude)| {
+                if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+                    return Err(ForecastError::InvalidRequest(format!(
+                        "coordinates ({}, {}) are out of range",
+                        latitude, longitude
+                    )));
+                }
+
+                self.clone().with_coordinates(latitude, longitude).try_build()
+            })
+            .collect()
+    }
+
+    /// The URL the request would be sent to, without building it. Note that
+    /// it contains the API key.
+    pub fn to_url(&self) -> Url {
+        self.build_url()
+    }
+
+    fn build_url(&self) -> Url {
+        let url_string = format!(
+            "{base}/{key}/{lat:.precision$},{long:.precision$}",
+            base = FORECAST_URL,
+            key = &self.api_key,
+            lat = &self.latitude,
+            long = &self.longitude,
+            precision = self.coordinate_precision
+        );
+
+        let mut url = Url::parse(&url_string).unwrap();
+
+        {
+            let mut query_pairs = url.query_pairs_mut();
+
+            if !self.exclude.is_empty() {
+                let excludes = join(
+                    self.exclude.iter().map(|block| block.as_query_str()),
+                    ","
+                );
+
+                query_pairs.append_pair(EXCLUDE, &excludes);
+            }
 
-    pub flags: Option<Flags>
+            if let Some(extend) = &self.extend {
+                query_pairs.append_pair(EXTEND, extend.as_query_str());
+            }
+
+            if let Some(lang) = &self.lang {
+                query_pairs.append_pair(LANG, lang.as_query_str());
+            }
+
+            if let Some(units) = &self.units {
+                query_pairs.append_pair(UNITS, units.as_query_str());
+            }
+
+            if let Some(version) = self.version {
+                query_pairs.append_pair(VERSION, &version.to_string());
+            }
+
+            query_pairs.extend_pairs(&self.query_params);
+        };
+
+        url
+    }
 }
 
-// unit tests
+/// Model object representing a request to the Time Machine API.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeMachineRequest<'a> {
+    api_key: Cow<'a, str>,
+    latitude: f64,
+    longitude: f64,
+    time: u64,
+    url: Url,
+    exclude: Vec<ExcludeBlock>,
+    lang: Option<Lang>,
+    units: Option<Units>
+}
 
-#[cfg(test)]
-mod tests {
-    use super::{ForecastRequestBuilder, ForecastRequest, TimeMachineRequestBuilder,
-                TimeMachineRequest, ExcludeBlock, Units, Lang, ExtendBy, FORECAST_URL, EXCLUDE,
-                EXTEND, LANG, UNITS};
+impl<'a> TimeMachineRequest<'a> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        api_key: impl Into<Cow<'a, str>>,
+        latitude: f64,
+        longitude: f64,
+        time: u64,
+        url: Url,
+        exclude: Vec<ExcludeBlock>,
+        lang: Option<Lang>,
+        units: Option<Units>
+    ) -> TimeMachineRequest<'a> {
+        TimeMachineRequest {
+            api_key: api_key.into(),
+            latitude,
+            longitude,
+            time,
+            url,
+            exclude,
+            lang,
+            units
+        }
+    }
+
+    /// The API key the request is authenticated with.
+    pub fn api_key(&self) -> &str {
+        &self.api_key
+    }
 
-    use reqwest::Url;
+    /// The requested latitude.
+    pub fn latitude(&self) -> f64 {
+        self.latitude
+    }
 
-    use serde_json;
+    /// The requested longitude.
+    pub fn longitude(&self) -> f64 {
+        self.longitude
+    }
 
-    use std::vec::Vec;
+    /// The requested time, in seconds since the UNIX epoch.
+    pub fn time(&self) -> u64 {
+        self.time
+    }
 
-    // constants
+    /// The DataBlocks excluded from the response.
+    pub fn exclude(&self) -> &[ExcludeBlock] {
+        &self.exclude
+    }
 
-    const LAT: f64 = 6.66;
-    const LONG: f64 = 66.6;
-    const TIME: u64 = 666;
+    /// The requested language, if any.
+    pub fn lang(&self) -> Option<&Lang> {
+        self.lang.as_ref()
+    }
+
+    /// The requested measurement units, if any.
+    pub fn units(&self) -> Option<&Units> {
+        self.units.as_ref()
+    }
+
+    /// The URL this request will be sent to. Note that it contains the API
+    /// key.
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// A reqwest `RequestBuilder` for this request, for sending it through
+    /// your own HTTP stack rather than an `ApiClient`, e.g. to add headers or
+    /// middleware first.
+    pub fn to_reqwest(&self, client: &Client) -> RequestBuilder {
+        client.get(self.url.clone())
+    }
+
+    fn target(&self) -> RequestTarget<'_> {
+        RequestTarget {
+            endpoint: Endpoint::TimeMachine,
+            api_key: &self.api_key,
+            latitude: self.latitude,
+            longitude: self.longitude,
+            url: &self.url
+        }
+    }
+}
+
+/// Builder object used to construct a TimeMachineRequest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeMachineRequestBuilder<'a> {
+    api_key: Cow<'a, str>,
+    latitude: f64,
+    longitude: f64,
+    time: u64,
+    exclude: ExcludeSet,
+    lang: Option<Lang>,
+    units: Option<Units>,
+    version: Option<u8>,
+    query_params: Vec<(String, String)>,
+    coordinate_precision: usize
+}
+
+impl<'a> TimeMachineRequestBuilder<'a> {
+    /// A Time Machine API request is constructed with required params
+    /// `api_key`, `latitude`, `longitude`, and `time`. The `api_key` may be
+    /// either borrowed or owned, e.g. a `&str` or a `String`.
+    pub fn new(
+        api_key: impl Into<Cow<'a, str>>,
+        latitude: f64,
+        longitude: f64,
+        time: u64
+    ) -> TimeMachineRequestBuilder<'a> {
+        TimeMachineRequestBuilder {
+            api_key: api_key.into(),
+            latitude,
+            longitude,
+            time,
+            exclude: ExcludeSet::new(),
+            lang: None,
+            units: None,
+            version: None,
+            query_params: Vec::new(),
+            coordinate_precision: DEFAULT_COORDINATE_PRECISION
+        }
+    }
+
+    /// Construct a Time Machine API request from a `geo::Point`. The point's
+    /// `y` coordinate is taken as the latitude and its `x` coordinate as the
+    /// longitude.
+    #[cfg(feature = "geo")]
+    pub fn from_point(
+        api_key: impl Into<Cow<'a, str>>,
+        point: geo::Point<f64>,
+        time: u64
+    ) -> TimeMachineRequestBuilder<'a> {
+        TimeMachineRequestBuilder::new(api_key, point.y(), point.x(), time)
+    }
+
+    /// Construct a Time Machine API request for the instant `when`, which may
+    /// be expressed in any time zone (e.g. `DateTime<Utc>` or
+    /// `DateTime<FixedOffset>`). It is converted to UNIX seconds internally.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `when` is before the UNIX epoch.
+    #[cfg(feature = "chrono")]
+    pub fn new_at<Tz: chrono::TimeZone>(
+        api_key: impl Into<Cow<'a, str>>,
+        latitude: f64,
+        longitude: f64,
+        when: chrono::DateTime<Tz>
+    ) -> TimeMachineRequestBuilder<'a> {
+        let time = std::convert::TryFrom::try_from(when.timestamp())
+            .expect("Time Machine requests before the UNIX epoch are not supported");
+
+        TimeMachineRequestBuilder::new(api_key, latitude, longitude, time)
+    }
+
+    /// Add a DataBlock to exclude from the response.
+    pub fn exclude_block(mut self, exclude_block: ExcludeBlock) -> TimeMachineRequestBuilder<'a> {
+        self.exclude.insert(exclude_block);
+        self
+    }
+
+    /// Add multiple DataBlocks to exclude from the response.
+    ///
+    /// Note that this drains `exclude_blocks`, leaving the caller's `Vec`
+    /// empty. Use `exclude_all` to keep it intact.
+    pub fn exclude_blocks(
+        mut self,
+        exclude_blocks: &mut Vec<ExcludeBlock>
+    ) -> TimeMachineRequestBuilder<'a> {
+        self.exclude.extend(exclude_blocks.drain(..));
+        self
+    }
+
+    /// Add multiple DataBlocks to exclude from the response, cloning them
+    /// from `exclude_blocks`.
+    pub fn exclude_all(mut self, exclude_blocks: &[ExcludeBlock]) -> TimeMachineRequestBuilder<'a> {
+        self.exclude.extend(exclude_blocks.iter().cloned());
+        self
+    }
+
+    /// Exclude every DataBlock except `block` from the response, replacing
+    /// any previously excluded blocks.
+    pub fn only(self, block: ExcludeBlock) -> TimeMachineRequestBuilder<'a> {
+        self.only_blocks(&[block])
+    }
+
+    /// Exclude every DataBlock except `blocks` from the response, replacing
+    /// any previously excluded blocks.
+    pub fn only_blocks(mut self, blocks: &[ExcludeBlock]) -> TimeMachineRequestBuilder<'a> {
+        self.exclude = ExcludeBlock::all()
+            .iter()
+            .filter(|block| !blocks.contains(block))
+            .cloned()
+            .collect();
+        self
+    }
+
+    /// Remove every previously excluded DataBlock.
+    pub fn clear_excludes(mut self) -> TimeMachineRequestBuilder<'a> {
+        self.exclude.clear();
+        self
+    }
+
+    /// Remove `block` from the excluded DataBlocks, if present.
+    pub fn remove_exclude(mut self, block: &ExcludeBlock) -> TimeMachineRequestBuilder<'a> {
+        self.exclude.remove(block);
+        self
+    }
+
+    /// Returns true if `block` is currently excluded.
+    pub fn is_excluded(&self, block: &ExcludeBlock) -> bool {
+        self.exclude.contains(block)
+    }
+
+    /// Set the language for messages in the response data.
+    pub fn lang(mut self, lang: Lang) -> TimeMachineRequestBuilder<'a> {
+        self.lang = Some(lang);
+        self
+    }
+
+    /// Set the measurement units for response data.
+    pub fn units(mut self, units: Units) -> TimeMachineRequestBuilder<'a> {
+        self.units = Some(units);
+        self
+    }
+
+    /// Request a specific version of the Pirate Weather response format.
+    /// Version 2 adds the `fire_index`, `smoke`, `ice_accumulation`,
+    /// `liquid_accumulation` and `snow_accumulation` properties to data
+    /// points, along with additional `flags`. Without it the API responds
+    /// with version 1.
+    pub fn version(mut self, version: u8) -> TimeMachineRequestBuilder<'a> {
+        self.version = Some(version);
+        self
+    }
+
+    /// Add an arbitrary query parameter, e.g. an experimental one which this
+    /// crate doesn't model yet. These are appended after the modeled
+    /// parameters, and are percent-encoded as needed.
+    pub fn query_param(mut self, key: &str, value: &str) -> TimeMachineRequestBuilder<'a> {
+        self.query_params.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Set the number of decimal places the coordinates are formatted with
+    /// in the URL, in place of `DEFAULT_COORDINATE_PRECISION`.
+    pub fn coordinate_precision(mut self, precision: usize) -> TimeMachineRequestBuilder<'a> {
+        self.coordinate_precision = precision;
+        self
+    }
+
+    /// Finalize the request.
+    pub fn build(self) -> TimeMachineRequest<'a> {
+        let url = self.build_url();
+
+        TimeMachineRequest::new(
+            self.api_key,
+            self.latitude,
+            self.longitude,
+            self.time,
+            url,
+            self.exclude.to_vec(),
+            self.lang,
+            self.units
+        )
+    }
+
+    /// The URL the request would be sent to, without building it. Note that
+    /// it contains the API key.
+    pub fn to_url(&self) -> Url {
+        self.build_url()
+    }
+
+    fn build_url(&self) -> Url {
+        let url_string = format!(
+            "{base}/{key}/{lat:.precision$},{long:.precision$},{time}",
+            base = FORECAST_URL,
+            key = self.api_key,
+            lat = self.latitude,
+            long = self.longitude,
+            time = self.time,
+            precision = self.coordinate_precision
+        );
+
+        let mut url = Url::parse(&url_string).unwrap();
+
+        {
+            let mut query_pairs = url.query_pairs_mut();
+
+            if !self.exclude.is_empty() {
+                let excludes = join(
+                    self.exclude.iter().map(|block| block.as_query_str()),
+                    ","
+                );
+
+                query_pairs.append_pair(EXCLUDE, &excludes);
+            }
+
+            if let Some(lang) = &self.lang {
+                query_pairs.append_pair(LANG, lang.as_query_str());
+            }
+
+            if let Some(units) = &self.units {
+                query_pairs.append_pair(UNITS, units.as_query_str());
+            }
+
+            if let Some(version) = self.version {
+                query_pairs.append_pair(VERSION, &version.to_string());
+            }
+
+            query_pairs.extend_pairs(&self.query_params);
+        }
+
+        url
+    }
+}
+
+// data model objects
+//
+// Optional properties of the response models are omitted when serializing
+// rather than written as `null`; missing keys still deserialize to `None`.
+
+/// Model object representing an icon for display.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(remote = "Icon")]
+#[non_exhaustive]
+pub enum Icon {
+    #[serde(rename = "clear-day")]
+    ClearDay,
+
+    #[serde(rename = "clear-night")]
+    ClearNight,
+
+    #[serde(rename = "rain")]
+    Rain,
+
+    #[serde(rename = "snow")]
+    Snow,
+
+    #[serde(rename = "sleet")]
+    Sleet,
+
+    #[serde(rename = "wind")]
+    Wind,
+
+    #[serde(rename = "fog")]
+    Fog,
+
+    #[serde(rename = "cloudy")]
+    Cloudy,
+
+    #[serde(rename = "partly-cloudy-day")]
+    PartlyCloudyDay,
+
+    #[serde(rename = "partly-cloudy-night")]
+    PartlyCloudyNight,
+
+    #[serde(rename = "hail")]
+    Hail,
+
+    #[serde(rename = "thunderstorm")]
+    Thunderstorm,
+
+    #[serde(rename = "tornado")]
+    Tornado,
+
+    /// An icon this version of the crate does not recognize, holding the raw
+    /// value sent by the API.
+    #[serde(skip)]
+    Unknown(String)
+}
+
+// This is needed so that unrecognized icons deserialize to `Icon::Unknown`
+// rather than failing the whole response.
+impl <'de> Deserialize<'de> for Icon {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de>
+    {
+        let value = String::deserialize(deserializer)?;
+
+        let known: Result<Icon, serde::de::value::Error> =
+            Icon::deserialize(value.as_str().into_deserializer());
+
+        Ok(known.unwrap_or(Icon::Unknown(value)))
+    }
+}
+
+// This is needed so that `Icon::Unknown` serializes back to its raw value.
+impl Serialize for Icon {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        match *self {
+            Icon::Unknown(ref value) => serializer.serialize_str(value),
+            _ => Icon::serialize(self, serializer)
+        }
+    }
+}
+
+impl Icon {
+    /// An emoji suitable for displaying this icon.
+    pub fn emoji(&self) -> &'static str {
+        match *self {
+            Icon::ClearDay => "☀️",
+            Icon::ClearNight => "🌙",
+            Icon::Rain => "🌧️",
+            Icon::Snow => "🌨️",
+            Icon::Sleet => "❄️",
+            Icon::Wind => "💨",
+            Icon::Fog => "🌫️",
+            Icon::Cloudy => "☁️",
+            Icon::PartlyCloudyDay => "⛅",
+            Icon::PartlyCloudyNight => "☁️🌙",
+            Icon::Hail => "🧊",
+            Icon::Thunderstorm => "⛈️",
+            Icon::Tornado => "🌪️",
+            Icon::Unknown(_) => "❔"
+        }
+    }
+
+    /// A short, human-readable description of this icon.
+    pub fn description(&self) -> &'static str {
+        match *self {
+            Icon::ClearDay => "clear",
+            Icon::ClearNight => "clear night",
+            Icon::Rain => "rain",
+            Icon::Snow => "snow",
+            Icon::Sleet => "sleet",
+            Icon::Wind => "windy",
+            Icon::Fog => "foggy",
+            Icon::Cloudy => "cloudy",
+            Icon::PartlyCloudyDay => "partly cloudy",
+            Icon::PartlyCloudyNight => "partly cloudy night",
+            Icon::Hail => "hail",
+            Icon::Thunderstorm => "thunderstorms",
+            Icon::Tornado => "tornado",
+            Icon::Unknown(_) => "unknown conditions"
+        }
+    }
+
+    // How severe the conditions this icon shows are, used to break ties in
+    // `DataBlock::dominant_icon`.
+    fn severity_rank(&self) -> u8 {
+        match *self {
+            Icon::Unknown(_) => 0,
+            Icon::ClearDay | Icon::ClearNight => 1,
+            Icon::PartlyCloudyDay | Icon::PartlyCloudyNight => 2,
+            Icon::Cloudy => 3,
+            Icon::Fog => 4,
+            Icon::Wind => 5,
+            Icon::Rain => 6,
+            Icon::Sleet => 7,
+            Icon::Snow => 8,
+            Icon::Hail => 9,
+            Icon::Thunderstorm => 10,
+            Icon::Tornado => 11
+        }
+    }
+}
+
+/// Model object representing the kind of precipitation occurring at a particular
+/// time.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(remote = "PrecipType")]
+#[non_exhaustive]
+pub enum PrecipType {
+    #[serde(rename = "rain")]
+    Rain,
+
+    #[serde(rename = "snow")]
+    Snow,
+
+    #[serde(rename = "sleet")]
+    Sleet,
+
+    /// A kind of precipitation this version of the crate does not recognize,
+    /// e.g. `"hail"` or `"none"`, holding the raw value sent by the API.
+    #[serde(skip)]
+    Other(String)
+}
+
+// This is needed so that unrecognized precipitation types deserialize to
+// `PrecipType::Other` rather than failing the whole response.
+impl <'de> Deserialize<'de> for PrecipType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de>
+    {
+        let value = String::deserialize(deserializer)?;
+
+        let known: Result<PrecipType, serde::de::value::Error> =
+            PrecipType::deserialize(value.as_str().into_deserializer());
+
+        Ok(known.unwrap_or(PrecipType::Other(value)))
+    }
+}
+
+// This is needed so that `PrecipType::Other` serializes back to its raw value.
+impl Serialize for PrecipType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        match *self {
+            PrecipType::Other(ref value) => serializer.serialize_str(value),
+            _ => PrecipType::serialize(self, serializer)
+        }
+    }
+}
+
+/// Model object representing a DataBlock to exclude from the response.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub enum ExcludeBlock {
+    #[serde(rename = "currently")]
+    Currently,
+
+    #[serde(rename = "minutely")]
+    Minutely,
+
+    #[serde(rename = "hourly")]
+    Hourly,
+
+    #[serde(rename = "daily")]
+    Daily,
+
+    #[serde(rename = "alerts")]
+    Alerts,
+
+    #[serde(rename = "flags")]
+    Flags
+}
+
+impl ExcludeBlock {
+    // This block's bit in an `ExcludeSet`.
+    fn bit(&self) -> u8 {
+        match *self {
+            ExcludeBlock::Currently => 1,
+            ExcludeBlock::Minutely => 1 << 1,
+            ExcludeBlock::Hourly => 1 << 2,
+            ExcludeBlock::Daily => 1 << 3,
+            ExcludeBlock::Alerts => 1 << 4,
+            ExcludeBlock::Flags => 1 << 5
+        }
+    }
+
+    /// Every DataBlock which may be excluded from a response.
+    pub fn all() -> [ExcludeBlock; 6] {
+        [
+            ExcludeBlock::Currently,
+            ExcludeBlock::Minutely,
+            ExcludeBlock::Hourly,
+            ExcludeBlock::Daily,
+            ExcludeBlock::Alerts,
+            ExcludeBlock::Flags
+        ]
+    }
+
+    /// The name used for this block in the `exclude` query parameter, e.g.
+    /// `"hourly"`.
+    pub fn as_query_str(&self) -> &'static str {
+        match *self {
+            ExcludeBlock::Currently => "currently",
+            ExcludeBlock::Minutely => "minutely",
+            ExcludeBlock::Hourly => "hourly",
+            ExcludeBlock::Daily => "daily",
+            ExcludeBlock::Alerts => "alerts",
+            ExcludeBlock::Flags => "flags"
+        }
+    }
+}
+
+/// Parses a block name as used in the `exclude` query parameter, e.g.
+/// `"hourly"`.
+impl FromStr for ExcludeBlock {
+    type Err = ParseCodeError;
+
+    fn from_str(s: &str) -> Result<ExcludeBlock, ParseCodeError> {
+        ExcludeBlock::all()
+            .iter()
+            .find(|block| block.as_query_str() == s)
+            .cloned()
+            .ok_or_else(|| ParseCodeError { kind: "exclude block", code: s.to_string() })
+    }
+}
+
+/// A set of DataBlocks to exclude from a response, which the request builders
+/// collect excluded blocks into. Each block is present at most once, and
+/// iteration is always in the order of `ExcludeBlock::all`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub struct ExcludeSet(u8);
+
+impl ExcludeSet {
+    /// An empty set.
+    pub fn new() -> ExcludeSet {
+        ExcludeSet(0)
+    }
+
+    /// Returns true if `block` is in the set.
+    pub fn contains(&self, block: &ExcludeBlock) -> bool {
+        self.0 & block.bit() != 0
+    }
+
+    /// Add `block` to the set, returning true if it wasn't already present.
+    pub fn insert(&mut self, block: ExcludeBlock) -> bool {
+        let inserted = !self.contains(&block);
+
+        self.0 |= block.bit();
+        inserted
+    }
+
+    /// Remove `block` from the set, returning true if it was present.
+    pub fn remove(&mut self, block: &ExcludeBlock) -> bool {
+        let removed = self.contains(block);
+
+        self.0 &= !block.bit();
+        removed
+    }
+
+    /// Remove every block from the set.
+    pub fn clear(&mut self) {
+        self.0 = 0;
+    }
+
+    /// Returns true if the set contains no blocks.
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// The number of blocks in the set.
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Iterate over the blocks in the set, in the order of
+    /// `ExcludeBlock::all`.
+    pub fn iter(&self) -> impl Iterator<Item = ExcludeBlock> {
+        let set = *self;
+
+        ExcludeBlock::all().to_vec().into_iter().filter(move |block| set.contains(block))
+    }
+
+    /// The blocks in the set, in the order of `ExcludeBlock::all`.
+    pub fn to_vec(&self) -> Vec<ExcludeBlock> {
+        self.iter().collect()
+    }
+}
+
+impl Extend<ExcludeBlock> for ExcludeSet {
+    fn extend<I: IntoIterator<Item = ExcludeBlock>>(&mut self, blocks: I) {
+        for block in blocks {
+            self.insert(block);
+        }
+    }
+}
+
+impl std::iter::FromIterator<ExcludeBlock> for ExcludeSet {
+    fn from_iter<I: IntoIterator<Item = ExcludeBlock>>(blocks: I) -> ExcludeSet {
+        let mut set = ExcludeSet::new();
+
+        set.extend(blocks);
+        set
+    }
+}
+
+impl From<Vec<ExcludeBlock>> for ExcludeSet {
+    fn from(blocks: Vec<ExcludeBlock>) -> ExcludeSet {
+        blocks.into_iter().collect()
+    }
+}
+
+impl From<ExcludeSet> for Vec<ExcludeBlock> {
+    fn from(set: ExcludeSet) -> Vec<ExcludeBlock> {
+        set.to_vec()
+    }
+}
+
+/// When present in a request, this feature causes response data to be reported
+/// for 168 hours into the future instead of 48 hours.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum ExtendBy {
+    #[serde(rename = "hourly")]
+    Hourly
+}
+
+impl ExtendBy {
+    /// The value used for this option in the `extend` query parameter.
+    pub fn as_query_str(&self) -> &'static str {
+        match *self {
+            ExtendBy::Hourly => "hourly"
+        }
+    }
+}
+
+/// Model object representing language.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(remote = "Lang")]
+#[non_exhaustive]
+pub enum Lang {
+    #[serde(rename = "ar")]
+    Arabic,
+
+    #[serde(rename = "az")]
+    Azerbaijani,
+
+    #[serde(rename = "be")]
+    Belarusian,
+
+    #[serde(rename = "bg")]
+    Bulgarian,
+
+    #[serde(rename = "bn")]
+    Bengali,
+
+    #[serde(rename = "bs")]
+    Bosnian,
+
+    #[serde(rename = "ca")]
+    Catalan,
+
+    // can also be deserialized from "cz", but will always be serialized to "cs"
+    #[serde(rename = "cs")]
+    Czech,
+
+    #[serde(rename = "cy")]
+    Welsh,
+
+    #[serde(rename = "da")]
+    Danish,
+
+    #[serde(rename = "de")]
+    German,
+
+    #[serde(rename = "el")]
+    Greek,
+
+    #[serde(rename = "en")]
+    English,
+
+    #[serde(rename = "eo")]
+    Esperanto,
+
+    #[serde(rename = "es")]
+    Spanish,
+
+    #[serde(rename = "et")]
+    Estonian,
+
+    #[serde(rename = "fa")]
+    Persian,
+
+    #[serde(rename = "fi")]
+    Finnish,
+
+    #[serde(rename = "fr")]
+    French,
+
+    #[serde(rename = "ga")]
+    Irish,
+
+    #[serde(rename = "gd")]
+    ScottishGaelic,
+
+    #[serde(rename = "he")]
+    Hebrew,
+
+    #[serde(rename = "hi")]
+    Hindi,
+
+    #[serde(rename = "hr")]
+    Croatian,
+
+    #[serde(rename = "hu")]
+    Hungarian,
+
+    #[serde(rename = "id")]
+    Indonesian,
+
+    #[serde(rename = "is")]
+    Icelandic,
+
+    #[serde(rename = "it")]
+    Italian,
+
+    #[serde(rename = "ja")]
+    Japanese,
+
+    #[serde(rename = "ka")]
+    Georgian,
+
+    #[serde(rename = "kn")]
+    Kannada,
+
+    #[serde(rename = "ko")]
+    Korean,
+
+    #[serde(rename = "kw")]
+    Cornish,
+
+    #[serde(rename = "lv")]
+    Latvian,
+
+    #[serde(rename = "ml")]
+    Malayalam,
+
+    #[serde(rename = "mr")]
+    Marathi,
+
+    // can also be deserialized from "no", but will always be serialized to "nb"
+    #[serde(rename = "nb")]
+    NorwegianBokmal,
+
+    #[serde(rename = "nl")]
+    Dutch,
+
+    #[serde(rename = "pa")]
+    Punjabi,
+
+    #[serde(rename = "pl")]
+    Polish,
+
+    #[serde(rename = "pt")]
+    Portuguese,
+
+    #[serde(rename = "ro")]
+    Romanian,
+
+    #[serde(rename = "ru")]
+    Russian,
+
+    #[serde(rename = "sk")]
+    Slovak,
+
+    #[serde(rename = "sl")]
+    Slovenian,
+
+    #[serde(rename = "sr")]
+    Serbian,
+
+    #[serde(rename = "sv")]
+    Swedish,
+
+    #[serde(rename = "ta")]
+    Tamil,
+
+    #[serde(rename = "te")]
+    Telugu,
+
+    #[serde(rename = "tet")]
+    Tetum,
+
+    #[serde(rename = "tr")]
+    Turkish,
+
+    #[serde(rename = "uk")]
+    Ukranian,
+
+    #[serde(rename = "ur")]
+    Urdu,
+
+    #[serde(rename = "x-pig-latin")]
+    IgpayAtinlay,
+
+    #[serde(rename = "zh")]
+    SimplifiedChinese,
+
+    #[serde(rename = "zh-tw")]
+    TraditionalChinese
+}
+
+impl Lang {
+    /// Every language supported by the API.
+    pub fn all() -> &'static [Lang] {
+        &[
+            Lang::Arabic,
+            Lang::Azerbaijani,
+            Lang::Belarusian,
+            Lang::Bulgarian,
+            Lang::Bengali,
+            Lang::Bosnian,
+            Lang::Catalan,
+            Lang::Czech,
+            Lang::Welsh,
+            Lang::Danish,
+            Lang::German,
+            Lang::Greek,
+            Lang::English,
+            Lang::Esperanto,
+            Lang::Spanish,
+            Lang::Estonian,
+            Lang::Persian,
+            Lang::Finnish,
+            Lang::French,
+            Lang::Irish,
+            Lang::ScottishGaelic,
+            Lang::Hebrew,
+            Lang::Hindi,
+            Lang::Croatian,
+            Lang::Hungarian,
+            Lang::Indonesian,
+            Lang::Icelandic,
+            Lang::Italian,
+            Lang::Japanese,
+            Lang::Georgian,
+            Lang::Kannada,
+            Lang::Korean,
+            Lang::Cornish,
+            Lang::Latvian,
+            Lang::Malayalam,
+            Lang::Marathi,
+            Lang::NorwegianBokmal,
+            Lang::Dutch,
+            Lang::Punjabi,
+            Lang::Polish,
+            Lang::Portuguese,
+            Lang::Romanian,
+            Lang::Russian,
+            Lang::Slovak,
+            Lang::Slovenian,
+            Lang::Serbian,
+            Lang::Swedish,
+            Lang::Tamil,
+            Lang::Telugu,
+            Lang::Tetum,
+            Lang::Turkish,
+            Lang::Ukranian,
+            Lang::Urdu,
+            Lang::IgpayAtinlay,
+            Lang::SimplifiedChinese,
+            Lang::TraditionalChinese
+        ]
+    }
+
+    /// The English name of this language, e.g. `"Simplified Chinese"`.
+    pub fn display_name(&self) -> &'static str {
+        match *self {
+            Lang::Arabic => "Arabic",
+            Lang::Azerbaijani => "Azerbaijani",
+            Lang::Belarusian => "Belarusian",
+            Lang::Bulgarian => "Bulgarian",
+            Lang::Bengali => "Bengali",
+            Lang::Bosnian => "Bosnian",
+            Lang::Catalan => "Catalan",
+            Lang::Czech => "Czech",
+            Lang::Welsh => "Welsh",
+            Lang::Danish => "Danish",
+            Lang::German => "German",
+            Lang::Greek => "Greek",
+            Lang::English => "English",
+            Lang::Esperanto => "Esperanto",
+            Lang::Spanish => "Spanish",
+            Lang::Estonian => "Estonian",
+            Lang::Persian => "Persian",
+            Lang::Finnish => "Finnish",
+            Lang::French => "French",
+            Lang::Irish => "Irish",
+            Lang::ScottishGaelic => "Scottish Gaelic",
+            Lang::Hebrew => "Hebrew",
+            Lang::Hindi => "Hindi",
+            Lang::Croatian => "Croatian",
+            Lang::Hungarian => "Hungarian",
+            Lang::Indonesian => "Indonesian",
+            Lang::Icelandic => "Icelandic",
+            Lang::Italian => "Italian",
+            Lang::Japanese => "Japanese",
+            Lang::Georgian => "Georgian",
+            Lang::Kannada => "Kannada",
+            Lang::Korean => "Korean",
+            Lang::Cornish => "Cornish",
+            Lang::Latvian => "Latvian",
+            Lang::Malayalam => "Malayalam",
+            Lang::Marathi => "Marathi",
+            Lang::NorwegianBokmal => "Norwegian Bokmål",
+            Lang::Dutch => "Dutch",
+            Lang::Punjabi => "Punjabi",
+            Lang::Polish => "Polish",
+            Lang::Portuguese => "Portuguese",
+            Lang::Romanian => "Romanian",
+            Lang::Russian => "Russian",
+            Lang::Slovak => "Slovak",
+            Lang::Slovenian => "Slovenian",
+            Lang::Serbian => "Serbian",
+            Lang::Swedish => "Swedish",
+            Lang::Tamil => "Tamil",
+            Lang::Telugu => "Telugu",
+            Lang::Tetum => "Tetum",
+            Lang::Turkish => "Turkish",
+            Lang::Ukranian => "Ukrainian",
+            Lang::Urdu => "Urdu",
+            Lang::IgpayAtinlay => "Pig Latin",
+            Lang::SimplifiedChinese => "Simplified Chinese",
+            Lang::TraditionalChinese => "Traditional Chinese"
+        }
+    }
+
+    /// The code used for this language in the `lang` query parameter, e.g.
+    /// `"zh-tw"`.
+    pub fn as_query_str(&self) -> &'static str {
+        match *self {
+            Lang::Arabic => "ar",
+            Lang::Azerbaijani => "az",
+            Lang::Belarusian => "be",
+            Lang::Bulgarian => "bg",
+            Lang::Bengali => "bn",
+            Lang::Bosnian => "bs",
+            Lang::Catalan => "ca",
+            Lang::Czech => "cs",
+            Lang::Welsh => "cy",
+            Lang::Danish => "da",
+            Lang::German => "de",
+            Lang::Greek => "el",
+            Lang::English => "en",
+            Lang::Esperanto => "eo",
+            Lang::Spanish => "es",
+            Lang::Estonian => "et",
+            Lang::Persian => "fa",
+            Lang::Finnish => "fi",
+            Lang::French => "fr",
+            Lang::Irish => "ga",
+            Lang::ScottishGaelic => "gd",
+            Lang::Hebrew => "he",
+            Lang::Hindi => "hi",
+            Lang::Croatian => "hr",
+            Lang::Hungarian => "hu",
+            Lang::Indonesian => "id",
+            Lang::Icelandic => "is",
+            Lang::Italian => "it",
+            Lang::Japanese => "ja",
+            Lang::Georgian => "ka",
+            Lang::Kannada => "kn",
+            Lang::Korean => "ko",
+            Lang::Cornish => "kw",
+            Lang::Latvian => "lv",
+            Lang::Malayalam => "ml",
+            Lang::Marathi => "mr",
+            Lang::NorwegianBokmal => "nb",
+            Lang::Dutch => "nl",
+            Lang::Punjabi => "pa",
+            Lang::Polish => "pl",
+            Lang::Portuguese => "pt",
+            Lang::Romanian => "ro",
+            Lang::Russian => "ru",
+            Lang::Slovak => "sk",
+            Lang::Slovenian => "sl",
+            Lang::Serbian => "sr",
+            Lang::Swedish => "sv",
+            Lang::Tamil => "ta",
+            Lang::Telugu => "te",
+            Lang::Tetum => "tet",
+            Lang::Turkish => "tr",
+            Lang::Ukranian => "uk",
+            Lang::Urdu => "ur",
+            Lang::IgpayAtinlay => "x-pig-latin",
+            Lang::SimplifiedChinese => "zh",
+            Lang::TraditionalChinese => "zh-tw"
+        }
+    }
+}
+
+/// Parses a language code as used in the `lang` query parameter, e.g. `"fr"`.
+/// Like deserialization, this accepts `"no"` as an alias of `"nb"` and the
+/// older `"cz"` as an alias of `"cs"`.
+impl FromStr for Lang {
+    type Err = ParseCodeError;
+
+    fn from_str(s: &str) -> Result<Lang, ParseCodeError> {
+        match s {
+            "ar" => Ok(Lang::Arabic),
+            "az" => Ok(Lang::Azerbaijani),
+            "be" => Ok(Lang::Belarusian),
+            "bg" => Ok(Lang::Bulgarian),
+            "bn" => Ok(Lang::Bengali),
+            "bs" => Ok(Lang::Bosnian),
+            "ca" => Ok(Lang::Catalan),
+            "cs" | "cz" => Ok(Lang::Czech),
+            "cy" => Ok(Lang::Welsh),
+            "da" => Ok(Lang::Danish),
+            "de" => Ok(Lang::German),
+            "el" => Ok(Lang::Greek),
+            "en" => Ok(Lang::English),
+            "eo" => Ok(Lang::Esperanto),
+            "es" => Ok(Lang::Spanish),
+            "et" => Ok(Lang::Estonian),
+            "fa" => Ok(Lang::Persian),
+            "fi" => Ok(Lang::Finnish),
+            "fr" => Ok(Lang::French),
+            "ga" => Ok(Lang::Irish),
+            "gd" => Ok(Lang::ScottishGaelic),
+            "he" => Ok(Lang::Hebrew),
+            "hi" => Ok(Lang::Hindi),
+            "hr" => Ok(Lang::Croatian),
+            "hu" => Ok(Lang::Hungarian),
+            "id" => Ok(Lang::Indonesian),
+            "is" => Ok(Lang::Icelandic),
+            "it" => Ok(Lang::Italian),
+            "ja" => Ok(Lang::Japanese),
+            "ka" => Ok(Lang::Georgian),
+            "kn" => Ok(Lang::Kannada),
+            "ko" => Ok(Lang::Korean),
+            "kw" => Ok(Lang::Cornish),
+            "lv" => Ok(Lang::Latvian),
+            "ml" => Ok(Lang::Malayalam),
+            "mr" => Ok(Lang::Marathi),
+            "nb" | "no" => Ok(Lang::NorwegianBokmal),
+            "nl" => Ok(Lang::Dutch),
+            "pa" => Ok(Lang::Punjabi),
+            "pl" => Ok(Lang::Polish),
+            "pt" => Ok(Lang::Portuguese),
+            "ro" => Ok(Lang::Romanian),
+            "ru" => Ok(Lang::Russian),
+            "sk" => Ok(Lang::Slovak),
+            "sl" => Ok(Lang::Slovenian),
+            "sr" => Ok(Lang::Serbian),
+            "sv" => Ok(Lang::Swedish),
+            "ta" => Ok(Lang::Tamil),
+            "te" => Ok(Lang::Telugu),
+            "tet" => Ok(Lang::Tetum),
+            "tr" => Ok(Lang::Turkish),
+            "uk" => Ok(Lang::Ukranian),
+            "ur" => Ok(Lang::Urdu),
+            "x-pig-latin" => Ok(Lang::IgpayAtinlay),
+            "zh" => Ok(Lang::SimplifiedChinese),
+            "zh-tw" => Ok(Lang::TraditionalChinese),
+            _ => Err(ParseCodeError { kind: "language", code: s.to_string() })
+        }
+    }
+}
+
+// This is needed to take into account the aliasing of "nb" as "no" and of
+// "cs" as "cz".
+// See https://github.com/serde-rs/serde/issues/1174#issuecomment-372411280
+impl <'de> Deserialize<'de> for Lang {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de>
+    {
+        let value = String::deserialize(deserializer)?;
+
+        match value.as_str() {
+            "no" => Ok(Lang::NorwegianBokmal),
+            "cz" => Ok(Lang::Czech),
+            _ => Lang::deserialize(value.into_deserializer())
+        }
+    }
+}
+
+// This is needed to take into account the aliasing of "nb" as "no".
+// See https://github.com/serde-rs/serde/issues/1174#issuecomment-372411280
+impl Serialize for Lang {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        Lang::serialize(self, serializer)
+    }
+}
+
+/// Model object representing measurement units.
+///
+/// Like the other enums modeling values defined by the API, this is
+/// `#[non_exhaustive]` so that variants can be added as the API grows, so
+/// matching on it outside this crate needs a wildcard arm:
+///
+/// ```compile_fail
+/// fn is_imperial(units: forecast::Units) -> bool {
+///     match units {
+///         forecast::Units::Imperial => true,
+///         forecast::Units::Auto | forecast::Units::CA | forecast::Units::UK |
+///         forecast::Units::SI => false
+///     }
+/// }
+/// ```
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum Units {
+    #[serde(rename = "auto")]
+    Auto,
+
+    #[serde(rename = "ca")]
+    CA,
+
+    #[serde(rename = "uk2")]
+    UK,
+
+    #[serde(rename = "us")]
+    Imperial,
+
+    #[serde(rename = "si")]
+    SI
+}
+
+impl Units {
+    /// Every system of units supported by the API.
+    pub fn all() -> &'static [Units] {
+        &[Units::Auto, Units::CA, Units::UK, Units::Imperial, Units::SI]
+    }
+
+    /// The code used for these units in the `units` query parameter, e.g.
+    /// `"si"`.
+    pub fn as_query_str(&self) -> &'static str {
+        match *self {
+            Units::Auto => "auto",
+            Units::CA => "ca",
+            Units::UK => "uk2",
+            Units::Imperial => "us",
+            Units::SI => "si"
+        }
+    }
+
+    /// The units the API would pick for `Units::Auto` in the country with the
+    /// ISO 3166-1 alpha-2 code `code`, compared case-insensitively:
+    ///
+    /// | Country        | Units             |
+    /// |----------------|-------------------|
+    /// | `US`           | `Units::Imperial` |
+    /// | `GB`           | `Units::UK`       |
+    /// | `CA`           | `Units::CA`       |
+    /// | anything else  | `Units::SI`       |
+    pub fn for_country(code: &str) -> Units {
+        match code.to_ascii_uppercase().as_str() {
+            "US" => Units::Imperial,
+            "GB" => Units::UK,
+            "CA" => Units::CA,
+            _ => Units::SI
+        }
+    }
+
+    /// Returns true for the metric systems, `Units::SI`, `Units::CA` and
+    /// `Units::UK`. Note that `Units::UK` still reports distances and speeds
+    /// in miles.
+    pub fn is_metric(&self) -> bool {
+        match *self {
+            Units::CA | Units::UK | Units::SI => true,
+            Units::Auto | Units::Imperial => false
+        }
+    }
+
+    /// The symbol for temperatures reported in these units, e.g. `"°C"`.
+    /// Returns `None` for `Units::Auto`, which the API resolves based on the
+    /// requested location.
+    pub fn temperature_unit(&self) -> Option<&'static str> {
+        self.temperature_scale().map(|scale| scale.symbol())
+    }
+
+    /// The scale temperatures are reported in with these units. Returns
+    /// `None` for `Units::Auto`.
+    pub fn temperature_scale(&self) -> Option<TempScale> {
+        match *self {
+            Units::Auto => None,
+            Units::Imperial => Some(TempScale::Fahrenheit),
+            Units::CA | Units::UK | Units::SI => Some(TempScale::Celsius)
+        }
+    }
+
+    /// The unit wind speeds are reported in with these units, e.g.
+    /// `SpeedUnit::MilesPerHour` for `Units::UK`. Returns `None` for
+    /// `Units::Auto`.
+    pub fn speed_unit(&self) -> Option<SpeedUnit> {
+        match *self {
+            Units::Auto => None,
+            Units::CA => Some(SpeedUnit::KilometresPerHour),
+            Units::UK | Units::Imperial => Some(SpeedUnit::MilesPerHour),
+            Units::SI => Some(SpeedUnit::MetresPerSecond)
+        }
+    }
+
+    // the number of kilometres in one unit of visibility
+    fn distance_scale(&self) -> Option<f64> {
+        match *self {
+            Units::Auto => None,
+            Units::CA | Units::SI => Some(1.0),
+            Units::UK | Units::Imperial => Some(METRES_PER_MILE / 1000.0)
+        }
+    }
+
+    // the number of metres per second in one unit of speed
+    fn speed_scale(&self) -> Option<f64> {
+        self.speed_unit().map(SpeedUnit::metres_per_second)
+    }
+
+    // the number of millimetres per hour in one unit of `precip_intensity`
+    fn precip_intensity_scale(&self) -> Option<f64> {
+        match *self {
+            Units::Auto => None,
+            Units::Imperial => Some(MILLIMETRES_PER_INCH),
+            Units::CA | Units::UK | Units::SI => Some(1.0)
+        }
+    }
+
+    // the amount of `precip_accumulation` (cm, or in for `Imperial`) in one
+    // hour of one unit of `precip_intensity` (mm/h, or in/h for `Imperial`)
+    fn precip_accumulation_scale(&self) -> Option<f64> {
+        match *self {
+            Units::Auto => None,
+            Units::Imperial => Some(1.0),
+            Units::CA | Units::UK | Units::SI => Some(0.1)
+        }
+    }
+}
+
+/// Parses a units code as used in the `units` query parameter, e.g. `"si"`.
+impl FromStr for Units {
+    type Err = ParseCodeError;
+
+    fn from_str(s: &str) -> Result<Units, ParseCodeError> {
+        match s {
+            "auto" => Ok(Units::Auto),
+            "ca" => Ok(Units::CA),
+            "uk2" => Ok(Units::UK),
+            "us" => Ok(Units::Imperial),
+            "si" => Ok(Units::SI),
+            _ => Err(ParseCodeError { kind: "units", code: s.to_string() })
+        }
+    }
+}
+
+/// Model object representing a temperature scale.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TempScale {
+    Celsius,
+    Fahrenheit
+}
+
+impl TempScale {
+    /// The symbol for temperatures in this scale, e.g. `"°C"`.
+    pub fn symbol(&self) -> &'static str {
+        match *self {
+            TempScale::Celsius => "°C",
+            TempScale::Fahrenheit => "°F"
+        }
+    }
+
+    // Convert a temperature in this scale to degrees Fahrenheit.
+    fn to_fahrenheit(self, temperature: f64) -> f64 {
+        match self {
+            TempScale::Celsius => temperature * 9.0 / 5.0 + 32.0,
+            TempScale::Fahrenheit => temperature
+        }
+    }
+
+    // Convert a temperature in this scale to kelvin.
+    fn to_kelvin(self, temperature: f64) -> f64 {
+        match self {
+            TempScale::Celsius => temperature + 273.15,
+            TempScale::Fahrenheit => (temperature - 32.0) * 5.0 / 9.0 + 273.15
+        }
+    }
+
+    // Convert a temperature in degrees Fahrenheit to this scale.
+    fn convert_fahrenheit(self, temperature: f64) -> f64 {
+        match self {
+            TempScale::Celsius => (temperature - 32.0) * 5.0 / 9.0,
+            TempScale::Fahrenheit => temperature
+        }
+    }
+}
+
+/// Model object representing a unit of speed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SpeedUnit {
+    MetresPerSecond,
+    KilometresPerHour,
+    MilesPerHour
+}
+
+impl SpeedUnit {
+    /// The symbol for speeds in this unit, e.g. `"km/h"`.
+    pub fn symbol(&self) -> &'static str {
+        match *self {
+            SpeedUnit::MetresPerSecond => "m/s",
+            SpeedUnit::KilometresPerHour => "km/h",
+            SpeedUnit::MilesPerHour => "mph"
+        }
+    }
+
+    // the number of metres per second in one of this unit
+    fn metres_per_second(self) -> f64 {
+        match self {
+            SpeedUnit::MetresPerSecond => 1.0,
+            SpeedUnit::KilometresPerHour => 1.0 / 3.6,
+            SpeedUnit::MilesPerHour => METRES_PER_MILE / 3600.0
+        }
+    }
+}
+
+/// Model object representing an Alert's severity. Severities are ordered from
+/// least to most severe, i.e. `Advisory < Watch < Warning`.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[non_exhaustive]
+pub enum Severity {
+    #[serde(rename = "advisory")]
+    Advisory,
+
+    #[serde(rename = "watch")]
+    Watch,
+
+    #[serde(rename = "warning")]
+    Warning
+}
+
+/// Model object representing a point on the 16-point compass.
+///
+/// When derived from a `DataPoint`'s `wind_bearing` this is the direction the
+/// wind is blowing *from*, with true north at 0 degrees.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Bearing {
+    North,
+    NorthNorthEast,
+    NorthEast,
+    EastNorthEast,
+    East,
+    EastSouthEast,
+    SouthEast,
+    SouthSouthEast,
+    South,
+    SouthSouthWest,
+    SouthWest,
+    WestSouthWest,
+    West,
+    WestNorthWest,
+    NorthWest,
+    NorthNorthWest
+}
+
+impl Bearing {
+    const ALL: [Bearing; 16] = [
+        Bearing::North,
+        Bearing::NorthNorthEast,
+        Bearing::NorthEast,
+        Bearing::EastNorthEast,
+        Bearing::East,
+        Bearing::EastSouthEast,
+        Bearing::SouthEast,
+        Bearing::SouthSouthEast,
+        Bearing::South,
+        Bearing::SouthSouthWest,
+        Bearing::SouthWest,
+        Bearing::WestSouthWest,
+        Bearing::West,
+        Bearing::WestNorthWest,
+        Bearing::NorthWest,
+        Bearing::NorthNorthWest
+    ];
+
+    /// Map a bearing in degrees clockwise from true north onto the nearest
+    /// compass point. Values outside `[0, 360)` wrap around, so both 0 and 360
+    /// are `North`. Returns `None` if `degrees` is not finite.
+    pub fn from_degrees(degrees: f64) -> Option<Bearing> {
+        if !degrees.is_finite() {
+            return None;
+        }
+
+        // each point covers 22.5 degrees, centered on its own heading
+        let index = ((degrees.rem_euclid(360.0) + 11.25) / 22.5) as usize % 16;
+
+        Some(Bearing::ALL[index])
+    }
+
+    /// The conventional abbreviation for this compass point, e.g. `"NNE"`.
+    pub fn abbreviation(&self) -> &'static str {
+        match *self {
+            Bearing::North => "N",
+            Bearing::NorthNorthEast => "NNE",
+            Bearing::NorthEast => "NE",
+            Bearing::EastNorthEast => "ENE",
+            Bearing::East => "E",
+            Bearing::EastSouthEast => "ESE",
+            Bearing::SouthEast => "SE",
+            Bearing::SouthSouthEast => "SSE",
+            Bearing::South => "S",
+            Bearing::SouthSouthWest => "SSW",
+            Bearing::SouthWest => "SW",
+            Bearing::WestSouthWest => "WSW",
+            Bearing::West => "W",
+            Bearing::WestNorthWest => "WNW",
+            Bearing::NorthWest => "NW",
+            Bearing::NorthNorthWest => "NNW"
+        }
+    }
+}
+
+impl fmt::Display for Bearing {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.abbreviation())
+    }
+}
+
+/// Model object representing one of the eight named phases of the moon.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MoonPhase {
+    NewMoon,
+    WaxingCrescent,
+    FirstQuarter,
+    WaxingGibbous,
+    FullMoon,
+    WaningGibbous,
+    LastQuarter,
+    WaningCrescent
+}
+
+impl MoonPhase {
+    const ALL: [MoonPhase; 8] = [
+        MoonPhase::NewMoon,
+        MoonPhase::WaxingCrescent,
+        MoonPhase::FirstQuarter,
+        MoonPhase::WaxingGibbous,
+        MoonPhase::FullMoon,
+        MoonPhase::WaningGibbous,
+        MoonPhase::LastQuarter,
+        MoonPhase::WaningCrescent
+    ];
+
+    /// Bucket a lunation fraction, as reported in `DataPoint::moon_phase`, into
+    /// the nearest named phase. 0 and 1 are both a new moon, 0.25 the first
+    /// quarter, 0.5 a full moon and 0.75 the last quarter; each phase covers
+    /// the eighth of the cycle centered on it. Returns `None` if `fraction`
+    /// lies outside `[0, 1]`.
+    pub fn from_fraction(fraction: f64) -> Option<MoonPhase> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return None;
+        }
+
+        let index = ((fraction + 0.0625) / 0.125) as usize % 8;
+
+        Some(MoonPhase::ALL[index])
+    }
+
+    /// An emoji depicting this phase as seen from the northern hemisphere.
+    pub fn emoji(&self) -> &'static str {
+        match *self {
+            MoonPhase::NewMoon => "🌑",
+            MoonPhase::WaxingCrescent => "🌒",
+            MoonPhase::FirstQuarter => "🌓",
+            MoonPhase::WaxingGibbous => "🌔",
+            MoonPhase::FullMoon => "🌕",
+            MoonPhase::WaningGibbous => "🌖",
+            MoonPhase::LastQuarter => "🌗",
+            MoonPhase::WaningCrescent => "🌘"
+        }
+    }
+}
+
+/// Model object representing how humid the air feels, bucketed by dew point.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Comfort {
+    Dry,
+    Comfortable,
+    Sticky,
+    Oppressive
+}
+
+impl Comfort {
+    /// Bucket a dew point in the given scale into a comfort level: Dry (below
+    /// 50°F/10°C), Comfortable (50-60°F), Sticky (60-65°F) or Oppressive
+    /// (65°F/18.3°C and above).
+    pub fn from_dew_point(dew_point: f64, scale: TempScale) -> Comfort {
+        let dew_point = scale.to_fahrenheit(dew_point);
+
+        if dew_point >= 65.0 {
+            Comfort::Oppressive
+        } else if dew_point >= 60.0 {
+            Comfort::Sticky
+        } else if dew_point >= 50.0 {
+            Comfort::Comfortable
+        } else {
+            Comfort::Dry
+        }
+    }
+
+    /// The name of this comfort level, e.g. `"Sticky"`.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Comfort::Dry => "Dry",
+            Comfort::Comfortable => "Comfortable",
+            Comfort::Sticky => "Sticky",
+            Comfort::Oppressive => "Oppressive"
+        }
+    }
+}
+
+/// Model object representing the WHO risk category of a UV index.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum UvRisk {
+    Low,
+    Moderate,
+    High,
+    VeryHigh,
+    Extreme
+}
+
+impl UvRisk {
+    /// Bucket a UV index into its risk category: Low (0-2), Moderate (3-5),
+    /// High (6-7), Very High (8-10) or Extreme (11+). The index is rounded to
+    /// the nearest whole number first; negative values are clamped to `Low`
+    /// and arbitrarily large ones to `Extreme`.
+    pub fn from_index(index: f64) -> UvRisk {
+        let index = index.round();
+
+        if index >= 11.0 {
+            UvRisk::Extreme
+        } else if index >= 8.0 {
+            UvRisk::VeryHigh
+        } else if index >= 6.0 {
+            UvRisk::High
+        } else if index >= 3.0 {
+            UvRisk::Moderate
+        } else {
+            UvRisk::Low
+        }
+    }
+
+    /// The name of this risk category, e.g. `"Very High"`.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            UvRisk::Low => "Low",
+            UvRisk::Moderate => "Moderate",
+            UvRisk::High => "High",
+            UvRisk::VeryHigh => "Very High",
+            UvRisk::Extreme => "Extreme"
+        }
+    }
+}
+
+/// Model object representing a force on the Beaufort wind scale.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Beaufort {
+    Calm,
+    LightAir,
+    LightBreeze,
+    GentleBreeze,
+    ModerateBreeze,
+    FreshBreeze,
+    StrongBreeze,
+    NearGale,
+    Gale,
+    StrongGale,
+    Storm,
+    ViolentStorm,
+    HurricaneForce
+}
+
+impl Beaufort {
+    const ALL: [Beaufort; 13] = [
+        Beaufort::Calm,
+        Beaufort::LightAir,
+        Beaufort::LightBreeze,
+        Beaufort::GentleBreeze,
+        Beaufort::ModerateBreeze,
+        Beaufort::FreshBreeze,
+        Beaufort::StrongBreeze,
+        Beaufort::NearGale,
+        Beaufort::Gale,
+        Beaufort::StrongGale,
+        Beaufort::Storm,
+        Beaufort::ViolentStorm,
+        Beaufort::HurricaneForce
+    ];
+
+    // the lower bound of each force above Calm, in m/s
+    const THRESHOLDS: [f64; 12] = [
+        0.5, 1.6, 3.4, 5.5, 8.0, 10.8, 13.9, 17.2, 20.8, 24.5, 28.5, 32.7
+    ];
+
+    /// Classify a wind speed in metres per second on the Beaufort scale.
+    pub fn from_ms(speed: f64) -> Beaufort {
+        let force = Beaufort::THRESHOLDS.iter()
+            .take_while(|threshold| speed >= **threshold)
+            .count();
+
+        Beaufort::ALL[force]
+    }
+
+    /// The Beaufort number of this force, from 0 (`Calm`) to 12
+    /// (`HurricaneForce`).
+    pub fn force(&self) -> u8 {
+        *self as u8
+    }
+}
+
+/// Model object containing various properties, each representing the average
+/// (unless otherwise specified) of a particular weather phenomenon occurring
+/// during a period of time.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DataPoint {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub apparent_temperature: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub apparent_temperature_high: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub apparent_temperature_high_time: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub apparent_temperature_low: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub apparent_temperature_low_time: Option<u64>,
+
+    #[deprecated(since = "1.0.0")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub apparent_temperature_max: Option<f64>,
+
+    #[deprecated(since = "1.0.0")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub apparent_temperature_max_time: Option<u64>,
+
+    #[deprecated(since = "1.0.0")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub apparent_temperature_min: Option<f64>,
+
+    #[deprecated(since = "1.0.0")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub apparent_temperature_min_time: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cloud_cover: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dew_point: Option<f64>,
+
+    /// The fire weather index (Pirate Weather only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fire_index: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub humidity: Option<f64>,
+
+    /// The accumulation of ice (Pirate Weather only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ice_accumulation: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<Icon>,
+
+    /// The accumulation of liquid precipitation (Pirate Weather only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub liquid_accumulation: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub moon_phase: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nearest_storm_bearing: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nearest_storm_distance: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ozone: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub precip_accumulation: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub precip_intensity: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub precip_intensity_max: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub precip_intensity_max_time: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub precip_probability: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub precip_type: Option<PrecipType>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pressure: Option<f64>,
+
+    /// The near-surface smoke concentration, in micrograms per cubic metre
+    /// (Pirate Weather only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smoke: Option<f64>,
+
+    /// The accumulation of snow (Pirate Weather only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snow_accumulation: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sunrise_time: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sunset_time: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature_high: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature_high_time: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature_low: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature_low_time: Option<u64>,
+
+    #[deprecated(since = "1.0.0")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature_max: Option<f64>,
+
+    #[deprecated(since = "1.0.0")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature_max_time: Option<u64>,
+
+    #[deprecated(since = "1.0.0")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature_min: Option<f64>,
+
+    #[deprecated(since = "1.0.0")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature_min_time: Option<u64>,
+
+    pub time: u64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uv_index: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uv_index_time: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wind_bearing: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wind_gust: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wind_gust_time: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wind_speed: Option<f64>,
+
+    /// Any properties not modeled above, e.g. ones added to the API after this
+    /// version of the crate was released.
+    #[cfg(feature = "json")]
+    #[serde(flatten, skip_serializing_if = "HashMap::is_empty")]
+    pub extra: HashMap<String, serde_json::Value>
+}
+
+impl DataPoint {
+    /// Compare two data points chronologically by their `time`.
+    pub fn cmp_by_time(&self, other: &DataPoint) -> Ordering {
+        self.time.cmp(&other.time)
+    }
+
+    /// A copy of this data point with the ratio properties `cloud_cover`,
+    /// `humidity` and `precip_probability` clamped to `[0, 1]`, since the API
+    /// occasionally reports values such as `1.02`. All other properties are
+    /// left untouched.
+    pub fn normalized(&self) -> DataPoint {
+        let mut data_point = self.clone();
+
+        for value in &mut [
+            &mut data_point.cloud_cover,
+            &mut data_point.humidity,
+            &mut data_point.precip_probability
+        ] {
+            if let Some(ref mut value) = **value {
+                *value = value.clamp(0.0, 1.0);
+            }
+        }
+
+        data_point
+    }
+
+    /// Returns true if `other` has the same properties as this data point,
+    /// with numeric properties differing by at most `epsilon` rather than
+    /// being exactly equal. Useful in tests, where values may have been
+    /// converted or round-tripped through JSON. A property present in one
+    /// data point but not the other is never equal, and neither is `NaN`.
+    pub fn approx_eq(&self, other: &DataPoint, epsilon: f64) -> bool {
+        let (mut left, mut right) = (self.clone(), other.clone());
+
+        let floats_match = left.float_fields_mut()
+            .into_iter()
+            .zip(right.float_fields_mut())
+            .all(|(left, right)| floats_approx_eq(*left, *right, epsilon));
+
+        for field in left.float_fields_mut().into_iter().chain(right.float_fields_mut()) {
+            *field = None;
+        }
+
+        floats_match && left == right
+    }
+
+    // Every property holding an `f64`.
+    #[allow(deprecated)]
+    fn float_fields_mut(&mut self) -> Vec<&mut Option<f64>> {
+        vec![
+            &mut self.apparent_temperature,
+            &mut self.apparent_temperature_high,
+            &mut self.apparent_temperature_low,
+            &mut self.apparent_temperature_max,
+            &mut self.apparent_temperature_min,
+            &mut self.cloud_cover,
+            &mut self.dew_point,
+            &mut self.fire_index,
+            &mut self.humidity,
+            &mut self.ice_accumulation,
+            &mut self.liquid_accumulation,
+            &mut self.moon_phase,
+            &mut self.nearest_storm_bearing,
+            &mut self.nearest_storm_distance,
+            &mut self.ozone,
+            &mut self.precip_accumulation,
+            &mut self.precip_intensity,
+            &mut self.precip_intensity_max,
+            &mut self.precip_probability,
+            &mut self.pressure,
+            &mut self.smoke,
+            &mut self.snow_accumulation,
+            &mut self.temperature,
+            &mut self.temperature_high,
+            &mut self.temperature_low,
+            &mut self.temperature_max,
+            &mut self.temperature_min,
+            &mut self.uv_index,
+            &mut self.visibility,
+            &mut self.wind_bearing,
+            &mut self.wind_gust,
+            &mut self.wind_speed
+        ]
+    }
+
+    // The properties which are ratios, and so should lie between 0 and 1.
+    fn ratio_fields(&self) -> [(&'static str, Option<f64>); 3] {
+        [
+            ("cloud_cover", self.cloud_cover),
+            ("humidity", self.humidity),
+            ("precip_probability", self.precip_probability)
+        ]
+    }
+
+    fn validate(
+        &self,
+        block: &'static str,
+        index: Option<usize>,
+        issues: &mut Vec<ValidationIssue>
+    ) {
+        for &(field, value) in self.ratio_fields().iter() {
+            if let Some(value) = value {
+                if !(0.0..=1.0).contains(&value) {
+                    issues.push(ValidationIssue {
+                        block,
+                        index,
+                        field,
+                        message: format!("{} is not between 0 and 1", value)
+                    });
+                }
+            }
+        }
+    }
+
+    /// The compass point the wind is blowing from, if `wind_bearing` is
+    /// present.
+    pub fn wind_bearing_cardinal(&self) -> Option<Bearing> {
+        self.wind_bearing.and_then(Bearing::from_degrees)
+    }
+
+    /// The 16-point compass abbreviation (e.g. `"NNE"`) for the direction the
+    /// wind is blowing from, if `wind_bearing` is present.
+    pub fn wind_cardinal(&self) -> Option<&'static str> {
+        self.wind_bearing_cardinal().map(|bearing| bearing.abbreviation())
+    }
+
+    /// The named phase of the moon, if `moon_phase` is present and within
+    /// `[0, 1]`.
+    pub fn moon_phase_named(&self) -> Option<MoonPhase> {
+        self.moon_phase.and_then(MoonPhase::from_fraction)
+    }
+
+    /// The wind speed in metres per second, given the `Units` the request was
+    /// made with. Returns `None` if `wind_speed` is absent or `from` is
+    /// `Units::Auto`.
+    pub fn wind_speed_ms(&self, from: Units) -> Option<f64> {
+        Some(self.wind_speed? * from.speed_scale()?)
+    }
+
+    /// The wind speed in kilometres per hour. See `wind_speed_ms`.
+    pub fn wind_speed_kmh(&self, from: Units) -> Option<f64> {
+        self.wind_speed_ms(from).map(|speed| speed * 3.6)
+    }
+
+    /// The wind speed in miles per hour. See `wind_speed_ms`.
+    pub fn wind_speed_mph(&self, from: Units) -> Option<f64> {
+        self.wind_speed_ms(from).map(|speed| speed * 3600.0 / METRES_PER_MILE)
+    }
+
+    /// The wind gust speed in metres per second, given the `Units` the request
+    /// was made with. Returns `None` if `wind_gust` is absent or `from` is
+    /// `Units::Auto`.
+    pub fn wind_gust_ms(&self, from: Units) -> Option<f64> {
+        Some(self.wind_gust? * from.speed_scale()?)
+    }
+
+    /// The wind gust speed in kilometres per hour. See `wind_gust_ms`.
+    pub fn wind_gust_kmh(&self, from: Units) -> Option<f64> {
+        self.wind_gust_ms(from).map(|speed| speed * 3.6)
+    }
+
+    /// The wind gust speed in miles per hour. See `wind_gust_ms`.
+    pub fn wind_gust_mph(&self, from: Units) -> Option<f64> {
+        self.wind_gust_ms(from).map(|speed| speed * 3600.0 / METRES_PER_MILE)
+    }
+
+    /// The temperature in kelvin, given the `Units` the request was made
+    /// with. Returns `None` if `temperature` is absent or `from` is
+    /// `Units::Auto`.
+    pub fn temperature_kelvin(&self, from: Units) -> Option<f64> {
+        Some(from.temperature_scale()?.to_kelvin(self.temperature?))
+    }
+
+    /// The apparent temperature in kelvin. See `temperature_kelvin`.
+    pub fn apparent_temperature_kelvin(&self, from: Units) -> Option<f64> {
+        Some(from.temperature_scale()?.to_kelvin(self.apparent_temperature?))
+    }
+
+    /// The daytime high temperature in kelvin. See `temperature_kelvin`.
+    pub fn temperature_high_kelvin(&self, from: Units) -> Option<f64> {
+        Some(from.temperature_scale()?.to_kelvin(self.temperature_high?))
+    }
+
+    /// The overnight low temperature in kelvin. See `temperature_kelvin`.
+    pub fn temperature_low_kelvin(&self, from: Units) -> Option<f64> {
+        Some(from.temperature_scale()?.to_kelvin(self.temperature_low?))
+    }
+
+    /// The dew point in kelvin. See `temperature_kelvin`.
+    pub fn dew_point_kelvin(&self, from: Units) -> Option<f64> {
+        Some(from.temperature_scale()?.to_kelvin(self.dew_point?))
+    }
+
+    /// The "feels like" temperature along with its scale, given the `Units`
+    /// the request was made with. This is `apparent_temperature`, falling back
+    /// to `temperature` when the apparent temperature is absent. Returns
+    /// `None` if both are absent or `from` is `Units::Auto`.
+    pub fn feels_like(&self, from: Units) -> Option<(f64, TempScale)> {
+        let temperature = self.apparent_temperature.or(self.temperature)?;
+
+        Some((temperature, from.temperature_scale()?))
+    }
+
+    /// The wind chill computed from `temperature` and `wind_speed` with the
+    /// NWS formula, in the temperature scale of the `Units` the request was
+    /// made with. The formula is only valid at or below 50°F (10°C) with wind
+    /// above 3 mph (4.8 km/h), so this returns `None` outside that range, if
+    /// either property is absent, or if `from` is `Units::Auto`.
+    pub fn wind_chill(&self, from: Units) -> Option<f64> {
+        let scale = from.temperature_scale()?;
+        let temperature = scale.to_fahrenheit(self.temperature?);
+        let wind_speed = self.wind_speed_mph(from)?;
+
+        if temperature > 50.0 || wind_speed <= 3.0 {
+            return None;
+        }
+
+        let wind = wind_speed.powf(0.16);
+
+        let wind_chill = 35.74 + 0.6215 * temperature - 35.75 * wind +
+            0.4275 * temperature * wind;
+
+        Some(scale.convert_fahrenheit(wind_chill))
+    }
+
+    /// The heat index computed from `temperature` and `humidity` with the NWS
+    /// Rothfusz regression, including its low and high humidity adjustments,
+    /// in the temperature scale of the `Units` the request was made with. The
+    /// regression is only valid at or above 80°F (26.7°C), so this returns
+    /// `None` below that, if either property is absent, or if `from` is
+    /// `Units::Auto`.
+    pub fn heat_index(&self, from: Units) -> Option<f64> {
+        let scale = from.temperature_scale()?;
+        let t = scale.to_fahrenheit(self.temperature?);
+        let rh = self.humidity? * 100.0;
+
+        if t < 80.0 {
+            return None;
+        }
+
+        let mut heat_index = -42.379 + 2.049_015_23 * t + 10.143_331_27 * rh -
+            0.224_755_41 * t * rh - 0.006_837_83 * t * t - 0.054_817_17 * rh * rh +
+            0.001_228_74 * t * t * rh + 0.000_852_82 * t * rh * rh -
+            0.000_001_99 * t * t * rh * rh;
+
+        if rh < 13.0 && t <= 112.0 {
+            heat_index -= (13.0 - rh) / 4.0 * ((17.0 - (t - 95.0).abs()) / 17.0).sqrt();
+        } else if rh > 85.0 && t <= 87.0 {
+            heat_index += (rh - 85.0) / 10.0 * (87.0 - t) / 5.0;
+        }
+
+        Some(scale.convert_fahrenheit(heat_index))
+    }
+
+    /// The sea-level air pressure in hectopascals. Every unit system reports
+    /// pressure in hectopascals (equivalently, millibars), so no conversion is
+    /// needed.
+    pub fn pressure_hpa(&self) -> Option<f64> {
+        self.pressure
+    }
+
+    /// The sea-level air pressure in inches of mercury.
+    pub fn pressure_inhg(&self) -> Option<f64> {
+        self.pressure.map(|pressure| pressure / HECTOPASCALS_PER_INCH_OF_MERCURY)
+    }
+
+    /// The visibility in kilometres, given the `Units` the request was made
+    /// with. Returns `None` if `visibility` is absent or `from` is
+    /// `Units::Auto`.
+    ///
+    /// Note that the API caps visibility at 10 of whatever distance unit it
+    /// reports in, i.e. 10 miles or 10 kilometres.
+    pub fn visibility_km(&self, from: Units) -> Option<f64> {
+        Some(self.visibility? * from.distance_scale()?)
+    }
+
+    /// The visibility in statute miles. See `visibility_km`.
+    pub fn visibility_miles(&self, from: Units) -> Option<f64> {
+        self.visibility_km(from).map(|distance| distance * 1000.0 / METRES_PER_MILE)
+    }
+
+    /// Returns true if this data point describes dangerous conditions: an
+    /// `icon` of `Icon::Thunderstorm`, `Icon::Tornado` or `Icon::Hail`, or a
+    /// `precip_intensity` of at least `HEAVY_PRECIP_INTENSITY` (7.6 mm/h or
+    /// 0.3 in/h) given the `Units` the request was made with. The intensity
+    /// is ignored if `from` is `Units::Auto`.
+    pub fn is_severe(&self, from: Units) -> bool {
+        let severe_icon = matches!(
+            self.icon,
+            Some(Icon::Thunderstorm) | Some(Icon::Tornado) | Some(Icon::Hail)
+        );
+
+        let heavy_precip = self.precip_intensity
+            .and_then(|intensity| Some(intensity * from.precip_intensity_scale()?))
+            .is_some_and(|intensity| intensity >= HEAVY_PRECIP_INTENSITY);
+
+        severe_icon || heavy_precip
+    }
+
+    /// The UV risk category, if `uv_index` is present and not NaN.
+    pub fn uv_risk(&self) -> Option<UvRisk> {
+        self.uv_index
+            .filter(|index| !index.is_nan())
+            .map(UvRisk::from_index)
+    }
+
+    /// How humid the air feels, given the `Units` the request was made with.
+    /// Returns `None` if `dew_point` is absent or `from` is `Units::Auto`.
+    pub fn comfort_level(&self, from: Units) -> Option<Comfort> {
+        Some(Comfort::from_dew_point(self.dew_point?, from.temperature_scale()?))
+    }
+
+    /// The relative humidity as a whole percentage, e.g. `63.0` for a
+    /// `humidity` of `0.634`.
+    pub fn relative_humidity_percent(&self) -> Option<f64> {
+        self.humidity.map(|humidity| (humidity * 100.0).round())
+    }
+}
+
+/// Sort `data` chronologically and remove data points sharing a timestamp.
+///
+/// The sort is stable, so of several data points with the same `time` the one
+/// which came first in `data` is kept.
+pub fn sort_and_dedup_by_time(data: &mut Vec<DataPoint>) {
+    data.sort_by(DataPoint::cmp_by_time);
+    data.dedup_by_key(|data_point| data_point.time);
+}
+
+/// Model object representing the various weather phenomena occurring over a
+/// period of time.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct DataBlock {
+    #[serde(deserialize_with = "deserialize_data_points")]
+    pub data: Vec<DataPoint>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<Icon>
+}
+
+// Deserialize a DataBlock's data, reporting the index of any data point which
+// fails to deserialize so that a single bad entry is easy to find.
+fn deserialize_data_points<'de, D>(deserializer: D) -> Result<Vec<DataPoint>, D::Error>
+    where D: Deserializer<'de>
+{
+    struct DataPointsVisitor;
+
+    impl<'de> Visitor<'de> for DataPointsVisitor {
+        type Value = Vec<DataPoint>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a sequence of data points")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Vec<DataPoint>, A::Error>
+            where A: SeqAccess<'de>
+        {
+            let mut data = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+
+            loop {
+                match seq.next_element() {
+                    Ok(Some(data_point)) => data.push(data_point),
+                    Ok(None) => return Ok(data),
+                    Err(err) => {
+                        return Err(de::Error::custom(
+                            format_args!("invalid data point at index {}: {}", data.len(), err)
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    deserializer.deserialize_seq(DataPointsVisitor)
+}
+
+impl DataBlock {
+    /// The largest value of `field` across this block's data, skipping data
+    /// points for which it is `None`. Returns `None` if there are no values.
+    ///
+    /// For example, `block.max_by_field(|d| d.temperature)`.
+    pub fn max_by_field<F>(&self, field: F) -> Option<f64>
+        where F: Fn(&DataPoint) -> Option<f64> {
+        self.data.iter().filter_map(field).fold(None, |max, value| match max {
+            Some(max) => Some(value.max(max)),
+            None => Some(value)
+        })
+    }
+
+    /// The smallest value of `field` across this block's data, skipping data
+    /// points for which it is `None`. Returns `None` if there are no values.
+    pub fn min_by_field<F>(&self, field: F) -> Option<f64>
+        where F: Fn(&DataPoint) -> Option<f64> {
+        self.data.iter().filter_map(field).fold(None, |min, value| match min {
+            Some(min) => Some(value.min(min)),
+            None => Some(value)
+        })
+    }
+
+    /// The mean value of `field` across this block's data, skipping data
+    /// points for which it is `None`. Returns `None` if there are no values.
+    pub fn average_field<F>(&self, field: F) -> Option<f64>
+        where F: Fn(&DataPoint) -> Option<f64> {
+        let (sum, count) = self.data.iter()
+            .filter_map(field)
+            .fold((0.0, 0), |(sum, count), value| (sum + value, count + 1));
+
+        if count == 0 {
+            None
+        } else {
+            Some(sum / f64::from(count))
+        }
+    }
+
+    /// The highest `precip_probability` across this block's data, skipping
+    /// data points without one. Returns `None` if there are no values.
+    pub fn max_precip_probability(&self) -> Option<f64> {
+        self.max_by_field(|d| d.precip_probability)
+    }
+
+    /// The `time` of the first data point whose `precip_probability` exceeds
+    /// `threshold`, e.g. when rain is expected to start in the `minutely`
+    /// block. Returns `None` if no data point exceeds it.
+    pub fn next_precip_time(&self, threshold: f64) -> Option<u64> {
+        self.data.iter()
+            .find(|d| precip_exceeds(d.precip_probability, threshold))
+            .map(|d| d.time)
+    }
+
+    /// The `time` of the first data point after precipitation starts (see
+    /// `next_precip_time`) whose `precip_probability` no longer exceeds
+    /// `threshold`. Returns `None` if precipitation never starts, or does not
+    /// clear before the end of the block.
+    pub fn precip_clears_at(&self, threshold: f64) -> Option<u64> {
+        self.data.iter()
+            .skip_while(|d| !precip_exceeds(d.precip_probability, threshold))
+            .find(|d| !precip_exceeds(d.precip_probability, threshold))
+            .map(|d| d.time)
+    }
+
+    /// Returns true if `other` has the same `summary` and `icon` as this
+    /// block, and data points which are pairwise equal according to
+    /// `DataPoint::approx_eq`.
+    pub fn approx_eq(&self, other: &DataBlock, epsilon: f64) -> bool {
+        self.summary == other.summary &&
+            self.icon == other.icon &&
+            self.data.len() == other.data.len() &&
+            self.data.iter().zip(&other.data).all(|(left, right)| left.approx_eq(right, epsilon))
+    }
+
+    /// The most frequent `icon` among this block's data points, e.g. to stand
+    /// in for a missing `daily` icon using that day's hourly data. Returns
+    /// `None` if no data point has an icon.
+    ///
+    /// Ties go to the more severe icon, from least to most severe: unknown,
+    /// clear (day or night), partly cloudy (day or night), cloudy, fog, wind,
+    /// rain, sleet, snow, hail, thunderstorm, tornado. Icons of equal severity
+    /// go to the one which appears first.
+    pub fn dominant_icon(&self) -> Option<Icon> {
+        let mut counts: Vec<(&Icon, usize)> = Vec::new();
+
+        for icon in self.data.iter().filter_map(|d| d.icon.as_ref()) {
+            match counts.iter_mut().find(|&&mut (counted, _)| counted == icon) {
+                Some(&mut (_, ref mut count)) => *count += 1,
+                None => counts.push((icon, 1))
+            }
+        }
+
+        counts.into_iter()
+            .rev()
+            .max_by_key(|&(icon, count)| (count, icon.severity_rank()))
+            .map(|(icon, _)| icon.clone())
+    }
+
+    /// The data points whose `time` is in `[start, end)`, assuming the data
+    /// is sorted by `time` as the API sends it.
+    pub fn between(&self, start: u64, end: u64) -> Vec<&DataPoint> {
+        let first = self.data.partition_point(|d| d.time < start);
+        let last = self.data.partition_point(|d| d.time < end).max(first);
+
+        self.data[first..last].iter().collect()
+    }
+
+    /// The total precipitation over this block, given the `Units` the request
+    /// was made with, in the units of `precip_accumulation`: centimetres, or
+    /// inches for `Units::Imperial`.
+    ///
+    /// Each data point contributes its `precip_accumulation`, or failing that
+    /// its `precip_intensity` over the time it covers. A data point is assumed
+    /// to cover the interval until the next one, i.e. 1 hour in the `hourly`
+    /// block, with the last covering the same interval as the one before it
+    /// and a lone data point covering 1 hour.
+    ///
+    /// Returns `None` if no data point has either property, or if an
+    /// intensity is needed and `from` is `Units::Auto`.
+    pub fn total_precip_accumulation(&self, from: Units) -> Option<f64> {
+        let mut total = None;
+
+        for (index, data_point) in self.data.iter().enumerate() {
+            let amount = match (data_point.precip_accumulation, data_point.precip_intensity) {
+                (Some(accumulation), _) => accumulation,
+                (None, Some(intensity)) => {
+                    intensity * self.interval_hours(index) * from.precip_accumulation_scale()?
+                },
+                (None, None) => continue
+            };
+
+            total = Some(total.unwrap_or(0.0) + amount);
+        }
+
+        total
+    }
+
+    // The number of hours covered by the data point at `index`.
+    fn interval_hours(&self, index: usize) -> f64 {
+        let next = self.data.get(index + 1).map(|next| (&self.data[index], next));
+        let previous = index.checked_sub(1)
+            .map(|previous| (&self.data[previous], &self.data[index]));
+
+        match next.or(previous) {
+            Some((start, end)) => end.time.saturating_sub(start.time) as f64 / 3600.0,
+            None => 1.0
+        }
+    }
+}
+
+// Whether two optional floats are both absent, or both present and within
+// `epsilon` of each other.
+fn floats_approx_eq(left: Option<f64>, right: Option<f64>, epsilon: f64) -> bool {
+    match (left, right) {
+        (Some(left), Some(right)) => left == right || (left - right).abs() <= epsilon,
+        (None, None) => true,
+        _ => false
+    }
+}
+
+// Whether two options are both absent, or both present and equal according
+// to `eq`.
+fn options_approx_eq<T, F>(left: Option<T>, right: Option<T>, eq: F) -> bool
+    where F: Fn(T, T) -> bool {
+    match (left, right) {
+        (Some(left), Some(right)) => eq(left, right),
+        (None, None) => true,
+        _ => false
+    }
+}
+
+// A missing probability is taken to mean no precipitation.
+fn precip_exceeds(probability: Option<f64>, threshold: f64) -> bool {
+    probability.is_some_and(|probability| probability > threshold)
+}
+
+/// Model object representing a severe weather warning issued by a government
+/// authority for the requested location.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Alert {
+    pub description: String,
+
+    pub expires: u64,
+
+    pub regions: Vec<String>,
+
+    pub severity: Severity,
+
+    pub time: u64,
+
+    pub title: String,
+
+    pub uri: String
+}
+
+impl Alert {
+    /// Returns true if this alert's severity is strictly greater than
+    /// `other`'s.
+    pub fn is_more_severe_than(&self, other: &Alert) -> bool {
+        self.severity > other.severity
+    }
+
+    /// Returns true if this alert is in effect at `now` (UNIX seconds), i.e.
+    /// `time <= now < expires`.
+    ///
+    /// Some feeds report `expires` as 0 to mean the alert has no expiry; such
+    /// alerts are treated as active from the moment they are issued.
+    pub fn is_active(&self, now: u64) -> bool {
+        self.time <= now && (self.expires == 0 || now < self.expires)
+    }
+}
+
+/// Model object representing a flag which contains miscellaneous metadata about
+/// a request.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub struct Flags {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub darksky_unavailable: Option<String>,
+
+    /// The distance to the nearest reporting station (Pirate Weather only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nearest_station: Option<f64>,
+
+    pub sources: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub units: Option<Units>,
+
+    /// The version of the API which served the response (Pirate Weather
+    /// only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>
+}
+
+impl Flags {
+    /// Returns true if the API reported that one of its data sources was
+    /// unavailable, meaning the response may be degraded.
+    pub fn is_data_source_unavailable(&self) -> bool {
+        self.darksky_unavailable.is_some()
+    }
+}
+
+/// The IANA time zone name of a response's location, e.g.
+/// `America/New_York`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Timezone(pub String);
+
+impl Timezone {
+    /// The time zone name as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The UTC offset in seconds observed in this time zone at `epoch` (UNIX
+    /// seconds). Unlike the deprecated `ApiResponse::offset`, this accounts
+    /// for DST transitions within the forecast window. Returns `None` if the
+    /// name isn't a recognized IANA time zone.
+    #[cfg(feature = "tz")]
+    pub fn offset_at(&self, epoch: u64) -> Option<i32> {
+        use chrono::Offset;
+
+        self.at(epoch).map(|local_time| local_time.offset().fix().local_minus_utc())
+    }
+
+    #[cfg(feature = "tz")]
+    fn at(&self, epoch: u64) -> Option<chrono::DateTime<chrono_tz::Tz>> {
+        let timezone: chrono_tz::Tz = self.0.parse().ok()?;
+        let seconds = std::convert::TryFrom::try_from(epoch).ok()?;
+
+        chrono::TimeZone::timestamp_opt(&timezone, seconds, 0).single()
+    }
+}
+
+impl fmt::Display for Timezone {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<String> for Timezone {
+    fn from(name: String) -> Timezone {
+        Timezone(name)
+    }
+}
+
+impl<'a> From<&'a str> for Timezone {
+    fn from(name: &'a str) -> Timezone {
+        Timezone(name.to_string())
+    }
+}
+
+/// Model object representing a Forecast or Time Machine API response.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiResponse {
+    pub latitude: f64,
+
+    pub longitude: f64,
+
+    pub timezone: String,
+
+    /// The location's UTC offset in hours at the time of the request. This is
+    /// a single static value and is wrong for any data point on the other
+    /// side of a DST transition; use `timezone_info().offset_at(..)` instead.
+    #[deprecated(since = "1.0.0")]
+    pub offset: i64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currently: Option<DataPoint>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minutely: Option<DataBlock>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hourly: Option<DataBlock>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub daily: Option<DataBlock>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alerts: Option<Vec<Alert>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flags: Option<Flags>,
+
+    /// Any properties not modeled above, e.g. ones added to the API after this
+    /// version of the crate was released.
+    #[cfg(feature = "json")]
+    #[serde(flatten, skip_serializing_if = "HashMap::is_empty")]
+    pub extra: HashMap<String, serde_json::Value>
+}
+
+impl ApiResponse {
+    /// Deserialize an ApiResponse from a JSON string, e.g. a response body
+    /// previously stored on disk.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ForecastError::Json` if `json` is not a valid response, or
+    /// `ForecastError::Deserialize` with the `verbose-errors` feature.
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &str) -> Result<ApiResponse, ForecastError> {
+        ApiResponse::from_slice(json.as_bytes())
+    }
+
+    /// Deserialize an ApiResponse from a slice of JSON bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ForecastError::Json` if `json` is not a valid response, or
+    /// `ForecastError::Deserialize` with the `verbose-errors` feature.
+    #[cfg(feature = "json")]
+    pub fn from_slice(json: &[u8]) -> Result<ApiResponse, ForecastError> {
+        #[cfg(feature = "verbose-errors")]
+        {
+            let mut deserializer = serde_json::Deserializer::from_slice(json);
+            let response = serde_path_to_error::deserialize(&mut deserializer)?;
+
+            deserializer.end()?;
+
+            Ok(response)
+        }
+
+        #[cfg(not(feature = "verbose-errors"))]
+        {
+            Ok(serde_json::from_slice(json)?)
+        }
+    }
+
+    /// Serialize this ApiResponse to a compact JSON string.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ForecastError::Json` if serialization fails.
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> Result<String, ForecastError> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Serialize this ApiResponse to an indented JSON string, e.g. for logging
+    /// or writing a test fixture.
+    ///
+    /// # Errors
+    ///
+    /// See `to_json`.
+    #[cfg(feature = "json")]
+    pub fn to_json_pretty(&self) -> Result<String, ForecastError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// The current temperature, if the `currently` block and its
+    /// `temperature` are present.
+    pub fn current_temperature(&self) -> Option<f64> {
+        self.currently.as_ref().and_then(|currently| currently.temperature)
+    }
+
+    /// The current summary, if the `currently` block and its `summary` are
+    /// present.
+    pub fn current_summary(&self) -> Option<&str> {
+        self.currently.as_ref().and_then(|currently| currently.summary.as_deref())
+    }
+
+    /// The current icon, if the `currently` block and its `icon` are present.
+    pub fn current_icon(&self) -> Option<&Icon> {
+        self.currently.as_ref().and_then(|currently| currently.icon.as_ref())
+    }
+
+    /// Iterate over every DataPoint in this response: `currently` first,
+    /// followed by the data of the `minutely`, `hourly` and `daily` blocks, in
+    /// that order. Absent blocks are skipped.
+    pub fn all_data_points(&self) -> impl Iterator<Item = &DataPoint> {
+        let blocks = [&self.minutely, &self.hourly, &self.daily];
+
+        self.currently.iter().chain(
+            IntoIterator::into_iter(blocks)
+                .filter_map(Option::as_ref)
+                .flat_map(|block| block.data.iter())
+        )
+    }
+
+    /// Returns true if this response was built without some of the API's data
+    /// sources and may be incomplete or stale. See
+    /// `Flags::is_data_source_unavailable`.
+    pub fn is_degraded(&self) -> bool {
+        self.flags.as_ref().is_some_and(Flags::is_data_source_unavailable)
+    }
+
+    /// The units the response's values are actually reported in, as given by
+    /// `flags.units`. After a request made with `Units::Auto` this tells you
+    /// which system the API chose for the location, e.g. to pass to helpers
+    /// such as `DataPoint::wind_speed_ms`.
+    pub fn resolved_units(&self) -> Option<&Units> {
+        self.flags.as_ref().and_then(|flags| flags.units.as_ref())
+    }
+
+    /// Returns false if `flags.nearest_station` is farther than
+    /// `max_station_km` kilometres away, meaning the data is interpolated from
+    /// distant stations and may not be worth alerting on. The distance is
+    /// converted from the `resolved_units`.
+    ///
+    /// Returns true when the distance is unknown, i.e. without `flags`, a
+    /// `nearest_station` or `units` to interpret it in, since that doesn't
+    /// show the data is unreliable.
+    pub fn is_reliable(&self, max_station_km: f64) -> bool {
+        let distance = self.flags.as_ref().and_then(|flags| flags.nearest_station);
+        let scale = self.resolved_units().and_then(Units::distance_scale);
+
+        match (distance, scale) {
+            (Some(distance), Some(scale)) => distance * scale <= max_station_km,
+            _ => true
+        }
+    }
+
+    /// Returns true if the `currently` data point is severe (see
+    /// `DataPoint::is_severe`, using `resolved_units`), or if any alert has
+    /// `Severity::Warning`.
+    pub fn has_severe_conditions(&self) -> bool {
+        let units = self.resolved_units().cloned().unwrap_or(Units::Auto);
+
+        let severe_currently = self.currently.as_ref()
+            .is_some_and(|currently| currently.is_severe(units));
+
+        let warning = self.alerts.as_ref()
+            .is_some_and(|alerts| alerts.iter().any(|alert| alert.severity == Severity::Warning));
+
+        severe_currently || warning
+    }
+
+    /// Whether the sun is up at `at` (UNIX seconds), according to the
+    /// `sunrise_time` and `sunset_time` of the day in the `daily` block which
+    /// contains it. Each day is taken to run until the next day's `time`, or
+    /// for 24 hours if it is the last.
+    ///
+    /// Near the poles a day may lack a sunrise or sunset. With only a
+    /// sunrise, it is daylight from sunrise onwards; with only a sunset, it is
+    /// daylight until sunset. Returns `None` if the day has neither (polar
+    /// day and polar night can't be told apart), or if no day in the `daily`
+    /// block contains `at`.
+    pub fn is_daylight(&self, at: u64) -> Option<bool> {
+        let data = &self.daily.as_ref()?.data;
+
+        let day = data.iter().enumerate().find(|&(index, day)| {
+            let end = data.get(index + 1).map_or(day.time.saturating_add(86_400), |next| next.time);
+
+            day.time <= at && at < end
+        })?.1;
+
+        match (day.sunrise_time, day.sunset_time) {
+            (Some(sunrise), Some(sunset)) => Some(sunrise <= at && at < sunset),
+            (Some(sunrise), None) => Some(sunrise <= at),
+            (None, Some(sunset)) => Some(at < sunset),
+            (None, None) => None
+        }
+    }
+
+    /// Returns true if the `precip_probability` of any data point in the
+    /// first 24 hours of the `hourly` block exceeds `threshold`, e.g. `0.3`.
+    /// Returns false if there is no `hourly` block.
+    pub fn umbrella_recommended(&self, threshold: f64) -> bool {
+        let start = match self.hourly.as_ref().and_then(|hourly| hourly.data.first()) {
+            Some(first) => first.time,
+            None => return false
+        };
+
+        self.hourly_window(start, start + 86_400)
+            .iter()
+            .any(|d| precip_exceeds(d.precip_probability, threshold))
+    }
+
+    /// The day in the `daily` block with the highest `temperature_high`,
+    /// skipping days without one. The earliest day wins a tie. Returns `None`
+    /// if there is no such day.
+    pub fn warmest_day(&self) -> Option<&DataPoint> {
+        self.daily.as_ref()?.data.iter()
+            .filter_map(|day| day.temperature_high.map(|high| (day, high)))
+            .fold(None, |warmest, (day, high)| match warmest {
+                Some((_, warmest_high)) if warmest_high >= high => warmest,
+                _ => Some((day, high))
+            })
+            .map(|(day, _)| day)
+    }
+
+    /// The day in the `daily` block with the lowest `temperature_low`,
+    /// skipping days without one. The earliest day wins a tie. Returns `None`
+    /// if there is no such day.
+    pub fn coldest_day(&self) -> Option<&DataPoint> {
+        self.daily.as_ref()?.data.iter()
+            .filter_map(|day| day.temperature_low.map(|low| (day, low)))
+            .fold(None, |coldest, (day, low)| match coldest {
+                Some((_, coldest_low)) if coldest_low <= low => coldest,
+                _ => Some((day, low))
+            })
+            .map(|(day, _)| day)
+    }
+
+    /// The data points of the `hourly` block whose `time` is in `[start, end)`,
+    /// e.g. for the next 6 hours. Returns an empty `Vec` if there is no
+    /// `hourly` block.
+    pub fn hourly_window(&self, start: u64, end: u64) -> Vec<&DataPoint> {
+        self.hourly.as_ref().map_or_else(Vec::new, |hourly| hourly.between(start, end))
+    }
+
+    /// The response's `timezone` as a `Timezone`, e.g. to look up its UTC
+    /// offset at a given point in time.
+    pub fn timezone_info(&self) -> Timezone {
+        Timezone::from(self.timezone.as_str())
+    }
+
+    /// `epoch` (UNIX seconds), e.g. a data point's `time`, in the response's
+    /// own `timezone`. Returns `None` if `timezone` isn't a recognized IANA
+    /// time zone name.
+    #[cfg(feature = "tz")]
+    pub fn local_time(&self, epoch: u64) -> Option<chrono::DateTime<chrono_tz::Tz>> {
+        self.timezone_info().at(epoch)
+    }
+
+    /// The location of this response as a `geo::Point`, with the longitude as
+    /// its `x` coordinate and the latitude as its `y` coordinate.
+    #[cfg(feature = "geo")]
+    pub fn location(&self) -> geo::Point<f64> {
+        geo::Point::new(self.longitude, self.latitude)
+    }
+
+    /// Returns true if `other` matches this response, with numeric properties
+    /// differing by at most `epsilon` rather than being exactly equal, e.g. to
+    /// compare responses in tests after a round trip through JSON. Data
+    /// points and blocks are compared with `DataPoint::approx_eq` and
+    /// `DataBlock::approx_eq`, `latitude`, `longitude` and the `flags`'
+    /// `nearest_station` within `epsilon`, and everything else exactly.
+    pub fn approx_eq(&self, other: &ApiResponse, epsilon: f64) -> bool {
+        let (mut left, mut right) = (self.clone(), other.clone());
+
+        let numbers_match =
+            floats_approx_eq(Some(left.latitude), Some(right.latitude), epsilon) &&
+            floats_approx_eq(Some(left.longitude), Some(right.longitude), epsilon) &&
+            options_approx_eq(
+                left.flags.as_mut().and_then(|flags| flags.nearest_station.take()),
+                right.flags.as_mut().and_then(|flags| flags.nearest_station.take()),
+                |left, right| floats_approx_eq(Some(left), Some(right), epsilon)
+            );
+
+        let data_match =
+            options_approx_eq(left.currently.take(), right.currently.take(), |left, right| {
+                left.approx_eq(&right, epsilon)
+            }) &&
+            vec![
+                (left.minutely.take(), right.minutely.take()),
+                (left.hourly.take(), right.hourly.take()),
+                (left.daily.take(), right.daily.take())
+            ].into_iter().all(|(left, right)| {
+                options_approx_eq(left, right, |left, right| left.approx_eq(&right, epsilon))
+            });
+
+        left.latitude = right.latitude;
+        left.longitude = right.longitude;
+
+        numbers_match && data_match && left == right
+    }
+
+    /// Check the response for values the API should never send: coordinates
+    /// out of range, data points out of chronological order, and ratios such
+    /// as `humidity`, `cloud_cover` and `precip_probability` outside `[0, 1]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns every problem found, rather than just the first.
+    pub fn validate(&self) -> Result<(), Vec<ValidationIssue>> {
+        let mut issues = Vec::new();
+
+        let coordinates = [("latitude", self.latitude, 90.0), ("longitude", self.longitude, 180.0)];
+
+        for &(field, value, limit) in coordinates.iter() {
+            if !(-limit..=limit).contains(&value) {
+                issues.push(ValidationIssue {
+                    block: "response",
+                    index: None,
+                    field,
+                    message: format!("{} is not between {} and {}", value, -limit, limit)
+                });
+            }
+        }
+
+        if let Some(ref currently) = self.currently {
+            currently.validate("currently", None, &mut issues);
+        }
+
+        let blocks = [
+            ("minutely", &self.minutely),
+            ("hourly", &self.hourly),
+            ("daily", &self.daily)
+        ];
+
+        for &(name, block) in blocks.iter() {
+            let data = match *block {
+                Some(ref block) => &block.data,
+                None => continue
+            };
+
+            for (index, data_point) in data.iter().enumerate() {
+                data_point.validate(name, Some(index), &mut issues);
+
+                if index > 0 && data_point.time <= data[index - 1].time {
+                    issues.push(ValidationIssue {
+                        block: name,
+                        index: Some(index),
+                        field: "time",
+                        message: format!(
+                            "{} is not after the preceding data point's time {}",
+                            data_point.time,
+                            data[index - 1].time
+                        )
+                    });
+                }
+            }
+        }
+
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues)
+        }
+    }
+
+    /// The alerts in this response which are in effect at `now` (UNIX
+    /// seconds). See `Alert::is_active`.
+    pub fn active_alerts(&self, now: u64) -> Vec<&Alert> {
+        match self.alerts {
+            Some(ref alerts) => alerts.iter().filter(|alert| alert.is_active(now)).collect(),
+            None => Vec::new()
+        }
+    }
+
+    /// Merge the hourly data points of `other` into this response's hourly
+    /// block, keeping them sorted by `time`. Where both responses have a data
+    /// point for the same hour, the one from `self` is kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `LocationMismatchError`, leaving `self` unchanged, if the
+    /// latitude or longitude of the two responses differ by more than
+    /// `LOCATION_TOLERANCE`.
+    pub fn merge_hourly(&mut self, other: &ApiResponse) -> Result<(), LocationMismatchError> {
+        if (self.latitude - other.latitude).abs() > LOCATION_TOLERANCE
+            || (self.longitude - other.longitude).abs() > LOCATION_TOLERANCE {
+            return Err(LocationMismatchError {
+                expected: (self.latitude, self.longitude),
+                found: (other.latitude, other.longitude)
+            });
+        }
+
+        let other_hourly = match other.hourly {
+            Some(ref hourly) => hourly,
+            None => return Ok(())
+        };
+
+        match self.hourly {
+            Some(ref mut hourly) => {
+                hourly.data.extend(other_hourly.data.iter().cloned());
+                sort_and_dedup_by_time(&mut hourly.data);
+            },
+            None => {
+                let mut hourly = other_hourly.clone();
+                sort_and_dedup_by_time(&mut hourly.data);
+                self.hourly = Some(hourly);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Renders a short, human-readable summary of the current conditions, e.g.
+/// `America/Los_Angeles: 14°C, Partly Cloudy, 20% chance of rain`. Any part
+/// which is missing from the response is omitted.
+impl fmt::Display for ApiResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.timezone)?;
+
+        let currently = match self.currently {
+            Some(ref currently) => currently,
+            None => return Ok(())
+        };
+
+        let mut parts = Vec::new();
+
+        if let Some(temperature) = currently.temperature {
+            let unit = self.flags.as_ref()
+                .and_then(|flags| flags.units)
+                .and_then(|units| units.temperature_unit())
+                .unwrap_or("°");
+
+            parts.push(format!("{:.0}{}", temperature, unit));
+        }
+
+        if let Some(ref summary) = currently.summary {
+            parts.push(summary.clone());
+        }
+
+        if let Some(probability) = currently.precip_probability {
+            let kind = match currently.precip_type {
+                Some(PrecipType::Rain) => "rain",
+                Some(PrecipType::Snow) => "snow",
+                Some(PrecipType::Sleet) => "sleet",
+                Some(PrecipType::Other(ref kind)) => kind.as_str(),
+                None => "precipitation"
+            };
+
+            parts.push(format!("{:.0}% chance of {}", probability * 100.0, kind));
+        }
+
+        if !parts.is_empty() {
+            write!(f, ": {}", parts.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Realistic API responses for use in tests, enabled with the `test-fixtures`
+/// feature.
+#[cfg(feature = "test-fixtures")]
+pub mod fixtures {
+    use super::ApiResponse;
+
+    static FORECAST_RESPONSE: &str =
+        include_str!("../resources/tests/forecast_response_full.json");
+
+    static CURRENTLY_ONLY_RESPONSE: &str =
+        include_str!("../resources/tests/forecast_response_currently_only.json");
+
+    static DARKSKY_UNAVAILABLE_RESPONSE: &str =
+        include_str!("../resources/tests/forecast_response_darksky_unavailable.json");
+
+    /// A full Forecast API response in `Units::Imperial`, with `currently`,
+    /// `minutely`, `hourly`, `daily`, `alerts` and `flags` blocks.
+    pub fn sample_forecast_response() -> ApiResponse {
+        ApiResponse::from_json(FORECAST_RESPONSE).unwrap()
+    }
+
+    /// A Forecast API response with only the `currently` and `flags` blocks.
+    pub fn sample_currently_only_response() -> ApiResponse {
+        ApiResponse::from_json(CURRENTLY_ONLY_RESPONSE).unwrap()
+    }
+
+    /// A degraded Forecast API response whose `flags` report
+    /// `darksky-unavailable`.
+    pub fn sample_darksky_unavailable_response() -> ApiResponse {
+        ApiResponse::from_json(DARKSKY_UNAVAILABLE_RESPONSE).unwrap()
+    }
+}
+
+// unit tests
+
+#[cfg(test)]
+mod tests {
+    use super::{ExcludeSet, ForecastRequestBuilder, SpeedUnit, ForecastRequest,
+                TimeMachineRequestBuilder, TimeMachineRequest, ExcludeBlock, Units, Lang, ExtendBy,
+                Bearing, MoonPhase, UvRisk, Beaufort, DataPoint, DataBlock, sort_and_dedup_by_time,
+                ParseCodeError, TempScale, Comfort, Icon, PrecipType, Severity, Alert, ApiResponse,
+                Flags, ApiClient, ApiClientBuilder, HttpTransport, TransportFuture, ForecastError,
+                CacheInfo, url_without_api_key, FORECAST_URL, EXCLUDE, EXTEND, LANG, UNITS};
+
+    #[cfg(feature = "json")]
+    use super::{WeatherProvider, ApiError, RequestTarget, Endpoint, LocationMismatchError,
+                HourlyDataScanner, ValidationIssue, ConditionalResponse, CachingApiClient};
+
+    #[cfg(any(feature = "json", feature = "tz"))]
+    use super::Timezone;
+
+    #[cfg(feature = "tracing")]
+    use super::redacted_url;
+
+    #[cfg(feature = "test-fixtures")]
+    use super::fixtures;
+
+    #[cfg(feature = "geocode")]
+    use super::{Geocoder, GeocodeError, GeocodeFuture, Place};
+
+    use reqwest::{Client, Response, Url};
+    use reqwest::header::{HeaderMap, HeaderValue, CACHE_CONTROL, EXPIRES, LAST_MODIFIED,
+                          USER_AGENT};
+
+    #[cfg(feature = "json")]
+    use reqwest::StatusCode;
+
+    #[cfg(feature = "json")]
+    use reqwest::header::IF_MODIFIED_SINCE;
+
+    #[cfg(feature = "json")]
+    use futures::stream::StreamExt;
+
+    use itertools::Itertools;
+
+    use std::cmp::Ordering;
+    use std::collections::HashSet;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    #[cfg(feature = "json")]
+    use std::time::Instant;
+
+    use std::vec::Vec;
+
+    // constants
+
+    const LAT: f64 = 6.66;
+    const LONG: f64 = 66.6;
+    const TIME: u64 = 666;
+
+    static API_KEY: &str = "some_api_key";
+
+    // tests for request models and their builders
+
+    #[test]
+    fn test_forecast_request_builder_defaults() {
+        let request = ForecastRequestBuilder::new(API_KEY, LAT, LONG).build();
+
+        let expected_url = Url::parse(&format!(
+            "{base}/{key}/{lat:.6},{long:.6}?",
+            base = FORECAST_URL,
+            key = API_KEY,
+            lat = LAT,
+            long = LONG
+        )).unwrap();
+
+        let expected = ForecastRequest::new(
+            API_KEY,
+            LAT,
+            LONG,
+            expected_url,
+            Vec::new(),
+            None,
+            None,
+            None
+        );
+
+        assert_eq!(expected.api_key, request.api_key);
+        assert_eq!(expected.latitude, request.latitude);
+        assert_eq!(expected.longitude, request.longitude);
+        assert_eq!(expected.exclude, request.exclude);
+        assert_eq!(expected.extend, request.extend);
+        assert_eq!(expected.lang, request.lang);
+        assert_eq!(expected.units, request.units);
+        assert_eq!(expected.url, request.url);
+
+        assert_eq!(expected, request);
+    }
+
+    #[test]
+    fn test_forecast_request_builder_simple() {
+        let mut blocks = vec![ExcludeBlock::Daily, ExcludeBlock::Alerts];
+
+        let request = ForecastRequestBuilder::new(API_KEY, LAT, LONG)
+            .exclude_block(ExcludeBlock::Hourly)
+            .exclude_blocks(&mut blocks)
+            .extend(ExtendBy::Hourly)
+            .lang(Lang::Arabic)
+            .units(Units::Imperial)
+            .build();
+
+        let expected_url = {
+            let mut url = Url::parse(&format!(
+                "{base}/{key}/{lat:.6},{long:.6}",
+                base = FORECAST_URL,
+                key = API_KEY,
+                lat = LAT,
+                long = LONG
+            )).unwrap();
+
+            url.query_pairs_mut()
+                .append_pair(EXCLUDE, "hourly,daily,alerts")
+                .append_pair(EXTEND, "hourly")
+                .append_pair(LANG, "ar")
+                .append_pair(UNITS, "us");
+
+            url
+        };
+
+        let expected = ForecastRequest::new(
+            API_KEY,
+            LAT,
+            LONG,
+            expected_url,
+            vec![
+                ExcludeBlock::Hourly,
+                ExcludeBlock::Daily,
+                ExcludeBlock::Alerts
+            ],
+            Some(ExtendBy::Hourly),
+            Some(Lang::Arabic),
+            Some(Units::Imperial)
+        );
+
+        assert_eq!(expected, request);
+    }
+
+    #[test]
+    fn test_forecast_request_builder_complex() {
+        let mut builder = ForecastRequestBuilder::new(API_KEY, LAT, LONG);
+        let mut blocks = vec![ExcludeBlock::Daily, ExcludeBlock::Alerts];
+
+        builder = builder.exclude_block(ExcludeBlock::Hourly);
+        builder = builder.exclude_blocks(&mut blocks);
+        builder = builder.extend(ExtendBy::Hourly);
+        builder = builder.lang(Lang::Arabic);
+        builder = builder.units(Units::Imperial);
+
+        let expected_url = {
+            let mut url = Url::parse(&format!(
+                "{base}/{key}/{lat:.6},{long:.6}",
+                base = FORECAST_URL,
+                key = API_KEY,
+                lat = LAT,
+                long = LONG
+            )).unwrap();
+
+            url.query_pairs_mut()
+                .append_pair(EXCLUDE, "hourly,daily,alerts")
+                .append_pair(EXTEND, "hourly")
+                .append_pair(LANG, "ar")
+                .append_pair(UNITS, "us");
+
+            url
+        };
+
+        let expected = ForecastRequest::new(
+            API_KEY,
+            LAT,
+            LONG,
+            expected_url,
+            vec![
+                ExcludeBlock::Hourly,
+                ExcludeBlock::Daily,
+                ExcludeBlock::Alerts
+            ],
+            Some(ExtendBy::Hourly),
+            Some(Lang::Arabic),
+            Some(Units::Imperial)
+        );
+
+        assert_eq!(expected, builder.build());
+    }
+
+    #[test]
+    fn test_time_machine_request_builder_defaults() {
+        let request = TimeMachineRequestBuilder::new(
+            API_KEY, LAT, LONG, TIME
+        ).build();
+
+        let expected_url = Url::parse(&format!(
+            "{base}/{key}/{lat:.6},{long:.6},{time}?",
+            base = FORECAST_URL,
+            key = API_KEY,
+            lat = LAT,
+            long = LONG,
+            time = TIME
+        )).unwrap();
+
+        let expected = TimeMachineRequest::new(
+            API_KEY,
+            LAT,
+            LONG,
+            TIME,
+            expected_url,
+            Vec::new(),
+            None,
+            None
+        );
+
+        assert_eq!(expected.api_key, request.api_key);
+        assert_eq!(expected.latitude, request.latitude);
+        assert_eq!(expected.longitude, request.longitude);
+        assert_eq!(expected.time, request.time);
+        assert_eq!(expected.exclude, request.exclude);
+        assert_eq!(expected.lang, request.lang);
+        assert_eq!(expected.units, request.units);
+        assert_eq!(expected.url, request.url);
+
+        assert_eq!(expected, request);
+    }
+
+    #[test]
+    fn test_time_machine_request_builder_simple() {
+        let mut blocks = vec![ExcludeBlock::Daily, ExcludeBlock::Alerts];
+
+        let request = TimeMachineRequestBuilder::new(API_KEY, LAT, LONG, TIME)
+            .exclude_block(ExcludeBlock::Hourly)
+            .exclude_blocks(&mut blocks)
+            .lang(Lang::Arabic)
+            .units(Units::Imperial)
+            .build();
+
+        let expected_url = {
+            let mut url = Url::parse(&format!(
+                "{base}/{key}/{lat:.6},{long:.6},{time}",
+                base = FORECAST_URL,
+                key = API_KEY,
+                lat = LAT,
+                long = LONG,
+                time = TIME
+            )).unwrap();
+
+            url.query_pairs_mut()
+                .append_pair(EXCLUDE, "hourly,daily,alerts")
+                .append_pair(LANG, "ar")
+                .append_pair(UNITS, "us");
+
+            url
+        };
+
+        let expected = TimeMachineRequest::new(
+            API_KEY,
+            LAT,
+            LONG,
+            TIME,
+            expected_url,
+            vec![
+                ExcludeBlock::Hourly,
+                ExcludeBlock::Daily,
+                ExcludeBlock::Alerts
+            ],
+            Some(Lang::Arabic),
+            Some(Units::Imperial)
+        );
+
+        assert_eq!(expected, request);
+    }
+
+    #[test]
+    fn test_time_machine_request_builder_complex() {
+        let mut builder = TimeMachineRequestBuilder::new(API_KEY, LAT, LONG, TIME);
+        let mut blocks = vec![ExcludeBlock::Daily, ExcludeBlock::Alerts];
+
+        builder = builder.exclude_block(ExcludeBlock::Hourly);
+        builder = builder.exclude_blocks(&mut blocks);
+        builder = builder.lang(Lang::Arabic);
+        builder = builder.units(Units::Imperial);
+
+        let expected_url = {
+            let mut url = Url::parse(&format!(
+                "{base}/{key}/{lat:.6},{long:.6},{time}",
+                base = FORECAST_URL,
+                key = API_KEY,
+                lat = LAT,
+                long = LONG,
+                time = TIME
+            )).unwrap();
+
+            url.query_pairs_mut()
+                .append_pair(EXCLUDE, "hourly,daily,alerts")
+                .append_pair(LANG, "ar")
+                .append_pair(UNITS, "us");
+
+            url
+        };
+
+        let expected = TimeMachineRequest::new(
+            API_KEY,
+            LAT,
+            LONG,
+            TIME,
+            expected_url,
+            vec![
+                ExcludeBlock::Hourly,
+                ExcludeBlock::Daily,
+                ExcludeBlock::Alerts
+            ],
+            Some(Lang::Arabic),
+            Some(Units::Imperial)
+        );
+
+        assert_eq!(expected, builder.build());
+    }
+
+    // Test that we can deserialize and serialize both variants "nb" and "no"
+    #[test]
+    fn test_norwegian_lang_serde() {
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        struct TestStruct {
+            no: Lang,
+            nb: Lang,
+            en: Lang
+        }
+
+        let test_json = "{\"nb\":\"nb\",\"no\":\"no\",\"en\":\"en\"}";
+
+        let test_struct: TestStruct = serde_json::from_str(test_json).unwrap();
+
+        assert_eq!(test_struct.nb, Lang::NorwegianBokmal);
+        assert_eq!(test_struct.no, Lang::NorwegianBokmal);
+        assert_eq!(test_struct.en, Lang::English);
+
+        let test_struct_serialized = serde_json::to_string(&test_struct).unwrap();
+
+        let test_struct_deserialized: TestStruct = serde_json::from_str(
+            test_struct_serialized.as_str()
+        ).unwrap();
+
+        assert_eq!(test_struct_deserialized.nb, Lang::NorwegianBokmal);
+        assert_eq!(test_struct_deserialized.no, Lang::NorwegianBokmal);
+        assert_eq!(test_struct_deserialized.en, Lang::English);
+    }
+
+    // tests for data model helpers
+
+    fn data_point_from_json(json: &str) -> DataPoint {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_bearing_from_degrees() {
+        assert_eq!(Some(Bearing::North), Bearing::from_degrees(0.0));
+        assert_eq!(Some(Bearing::North), Bearing::from_degrees(360.0));
+        assert_eq!(Some(Bearing::North), Bearing::from_degrees(348.75));
+        assert_eq!(Some(Bearing::North), Bearing::from_degrees(11.24));
+        assert_eq!(Some(Bearing::NorthNorthEast), Bearing::from_degrees(11.25));
+        assert_eq!(Some(Bearing::NorthNorthWest), Bearing::from_degrees(348.74));
+        assert_eq!(Some(Bearing::East), Bearing::from_degrees(90.0));
+        assert_eq!(Some(Bearing::SouthWest), Bearing::from_degrees(225.0));
+        assert_eq!(Some(Bearing::West), Bearing::from_degrees(-90.0));
+        assert_eq!(Some(Bearing::East), Bearing::from_degrees(450.0));
+        assert_eq!(None, Bearing::from_degrees(f64::NAN));
+
+        assert_eq!("WNW", Bearing::WestNorthWest.to_string());
+    }
+
+    #[test]
+    fn test_data_point_wind_cardinal() {
+        let data_point = data_point_from_json("{\"time\":0,\"windBearing\":22.5}");
+
+        assert_eq!(Some(Bearing::NorthNorthEast), data_point.wind_bearing_cardinal());
+        assert_eq!(Some("NNE"), data_point.wind_cardinal());
+
+        assert_eq!(None, data_point_from_json("{\"time\":0}").wind_cardinal());
+    }
+
+    #[test]
+    fn test_moon_phase_from_fraction() {
+        assert_eq!(Some(MoonPhase::NewMoon), MoonPhase::from_fraction(0.0));
+        assert_eq!(Some(MoonPhase::NewMoon), MoonPhase::from_fraction(1.0));
+        assert_eq!(Some(MoonPhase::NewMoon), MoonPhase::from_fraction(0.95));
+        assert_eq!(Some(MoonPhase::WaxingCrescent), MoonPhase::from_fraction(0.0625));
+        assert_eq!(Some(MoonPhase::FirstQuarter), MoonPhase::from_fraction(0.25));
+        assert_eq!(Some(MoonPhase::WaxingGibbous), MoonPhase::from_fraction(0.3125));
+        assert_eq!(Some(MoonPhase::FullMoon), MoonPhase::from_fraction(0.5));
+        assert_eq!(Some(MoonPhase::WaningGibbous), MoonPhase::from_fraction(0.6));
+        assert_eq!(Some(MoonPhase::LastQuarter), MoonPhase::from_fraction(0.75));
+        assert_eq!(Some(MoonPhase::WaningCrescent), MoonPhase::from_fraction(0.9));
+        assert_eq!(None, MoonPhase::from_fraction(-0.1));
+        assert_eq!(None, MoonPhase::from_fraction(1.1));
+        assert_eq!(None, MoonPhase::from_fraction(f64::NAN));
+
+        assert_eq!("🌕", MoonPhase::FullMoon.emoji());
+    }
+
+    #[test]
+    fn test_data_point_moon_phase_named() {
+        let data_point = data_point_from_json("{\"time\":0,\"moonPhase\":0.26}");
+
+        assert_eq!(Some(MoonPhase::FirstQuarter), data_point.moon_phase_named());
+        assert_eq!(None, data_point_from_json("{\"time\":0}").moon_phase_named());
+    }
+
+    #[test]
+    fn test_icon_emoji_and_description() {
+        assert_eq!("☀️", Icon::ClearDay.emoji());
+        assert_eq!("🌧️", Icon::Rain.emoji());
+        assert_eq!("🌪️", Icon::Tornado.emoji());
+        assert_eq!("partly cloudy", Icon::PartlyCloudyDay.description());
+        assert_eq!("hail", Icon::Hail.description());
+
+        let icons = [
+            Icon::ClearDay, Icon::ClearNight, Icon::Rain, Icon::Snow, Icon::Sleet, Icon::Wind,
+            Icon::Fog, Icon::Cloudy, Icon::PartlyCloudyDay, Icon::PartlyCloudyNight, Icon::Hail,
+            Icon::Thunderstorm, Icon::Tornado, Icon::Unknown("other".to_string())
+        ];
+        let emojis: HashSet<&str> = icons.iter().map(Icon::emoji).collect();
+
+        assert_eq!(icons.len(), emojis.len());
+    }
+
+    fn alert(severity: Severity, time: u64, expires: u64) -> Alert {
+        Alert {
+            description: String::new(),
+            expires,
+            regions: Vec::new(),
+            severity,
+            time,
+            title: String::new(),
+            uri: String::new()
+        }
+    }
+
+    #[test]
+    fn test_severity_ordering() {
+        assert!(Severity::Advisory < Severity::Watch);
+        assert!(Severity::Watch < Severity::Warning);
+
+        let alerts = [
+            alert(Severity::Watch, 0, 0),
+            alert(Severity::Warning, 0, 0),
+            alert(Severity::Advisory, 0, 0)
+        ];
+
+        let most_severe = alerts.iter().max_by_key(|a| a.severity.clone()).unwrap();
+
+        assert_eq!(Severity::Warning, most_severe.severity);
+        assert!(alerts[1].is_more_severe_than(&alerts[0]));
+        assert!(!alerts[0].is_more_severe_than(&alerts[0]));
+    }
+
+    #[test]
+    fn test_alert_is_active() {
+        let expiring = alert(Severity::Watch, 100, 200);
+
+        assert!(!expiring.is_active(99));
+        assert!(expiring.is_active(100));
+        assert!(expiring.is_active(199));
+        assert!(!expiring.is_active(200));
+
+        let open_ended = alert(Severity::Watch, 100, 0);
+
+        assert!(!open_ended.is_active(99));
+        assert!(open_ended.is_active(100));
+        assert!(open_ended.is_active(u64::MAX));
+    }
+
+    #[test]
+    #[cfg(feature = "geo")]
+    fn test_request_builders_from_point() {
+        let point = geo::Point::new(LONG, LAT);
+
+        assert_eq!(
+            ForecastRequestBuilder::new(API_KEY, LAT, LONG).build(),
+            ForecastRequestBuilder::from_point(API_KEY, point).build()
+        );
+
+        assert_eq!(
+            TimeMachineRequestBuilder::new(API_KEY, LAT, LONG, TIME).build(),
+            TimeMachineRequestBuilder::from_point(API_KEY, point, TIME).build()
+        );
+
+        let response: ApiResponse = serde_json::from_str(&format!(
+            "{{\"latitude\":{},\"longitude\":{},\"timezone\":\"UTC\",\"offset\":0}}",
+            LAT, LONG
+        )).unwrap();
+
+        assert_eq!(point, response.location());
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn test_time_machine_request_builder_new_at() {
+        use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+
+        let utc: DateTime<Utc> = Utc.timestamp_opt(TIME as i64, 0).unwrap();
+        let offset: DateTime<FixedOffset> = utc.with_timezone(
+            &FixedOffset::west_opt(5 * 3600).unwrap()
+        );
+
+        let expected = TimeMachineRequestBuilder::new(API_KEY, LAT, LONG, TIME).build();
+
+        assert_eq!(expected, TimeMachineRequestBuilder::new_at(API_KEY, LAT, LONG, utc).build());
+        assert_eq!(expected, TimeMachineRequestBuilder::new_at(API_KEY, LAT, LONG, offset).build());
+    }
+
+    #[test]
+    fn test_url_without_api_key() {
+        let request = ForecastRequestBuilder::new(API_KEY, LAT, LONG)
+            .units(Units::SI)
+            .build();
+
+        let key = url_without_api_key(API_KEY, &request.url);
+
+        assert!(!key.contains(API_KEY));
+        assert_eq!(
+            format!("{}/{:.6},{:.6}?units=si", FORECAST_URL, LAT, LONG),
+            key
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_caching_api_client_lookup() {
+        let reqwest_client = Client::new();
+        let response: ApiResponse = serde_json::from_str(
+            "{\"latitude\":0,\"longitude\":0,\"timezone\":\"UTC\",\"offset\":0}"
+        ).unwrap();
+
+        let fresh = CachingApiClient::new(&reqwest_client, Duration::from_secs(60));
+        fresh.cache.lock().unwrap().insert("k".to_string(), (Instant::now(), response.clone()));
+
+        assert_eq!(Some(response.clone()), fresh.lookup("k"));
+        assert_eq!(None, fresh.lookup("other"));
+
+        fresh.clear();
+
+        assert_eq!(None, fresh.lookup("k"));
+
+        let stale = CachingApiClient::new(&reqwest_client, Duration::from_secs(0));
+        stale.cache.lock().unwrap().insert("k".to_string(), (Instant::now(), response));
+
+        assert_eq!(None, stale.lookup("k"));
+        assert!(stale.cache.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_cache_info_from_headers() {
+        let mut headers = HeaderMap::new();
+
+        assert_eq!(CacheInfo::default(), CacheInfo::from_headers(&headers));
+
+        headers.insert(EXPIRES, HeaderValue::from_static("Thu, 01 Jan 1970 00:16:40 GMT"));
+
+        let expires_only = CacheInfo::from_headers(&headers);
+
+        assert_eq!(CacheInfo { max_age: None, expires: Some(1000) }, expires_only);
+        assert_eq!(Some(Duration::from_secs(400)), expires_only.time_to_live(600));
+        assert_eq!(Some(Duration::from_secs(0)), expires_only.time_to_live(2000));
+
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("public, max-age=300"));
+
+        let both = CacheInfo::from_headers(&headers);
+
+        assert_eq!(Some(Duration::from_secs(300)), both.max_age);
+        assert_eq!(Some(1000), both.expires);
+        assert_eq!(Some(Duration::from_secs(300)), both.time_to_live(600));
+
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+        headers.insert(EXPIRES, HeaderValue::from_static("0"));
+
+        assert_eq!(CacheInfo::default(), CacheInfo::from_headers(&headers));
+    }
+
+    #[test]
+    fn test_request_builders_only() {
+        let mut blocks = vec![ExcludeBlock::Daily];
+
+        let forecast_request = ForecastRequestBuilder::new(API_KEY, LAT, LONG)
+            .exclude_blocks(&mut blocks)
+            .only(ExcludeBlock::Currently)
+            .build();
+
+        assert_eq!(
+            vec![
+                ExcludeBlock::Minutely,
+                ExcludeBlock::Hourly,
+                ExcludeBlock::Daily,
+                ExcludeBlock::Alerts,
+                ExcludeBlock::Flags
+            ],
+            forecast_request.exclude
+        );
+
+        let time_machine_request = TimeMachineRequestBuilder::new(API_KEY, LAT, LONG, TIME)
+            .only_blocks(&[ExcludeBlock::Hourly, ExcludeBlock::Alerts])
+            .build();
+
+        assert_eq!(
+            vec![
+                ExcludeBlock::Currently,
+                ExcludeBlock::Minutely,
+                ExcludeBlock::Daily,
+                ExcludeBlock::Flags
+            ],
+            time_machine_request.exclude
+        );
+    }
+
+    #[test]
+    fn test_request_builders_clear_and_remove_excludes() {
+        let mut blocks = vec![ExcludeBlock::Daily, ExcludeBlock::Hourly];
+
+        let forecast_request = ForecastRequestBuilder::new(API_KEY, LAT, LONG)
+            .exclude_block(ExcludeBlock::Hourly)
+            .exclude_blocks(&mut blocks)
+            .remove_exclude(&ExcludeBlock::Hourly)
+            .build();
+
+        assert_eq!(vec![ExcludeBlock::Daily], forecast_request.exclude);
+
+        let time_machine_request = TimeMachineRequestBuilder::new(API_KEY, LAT, LONG, TIME)
+            .exclude_block(ExcludeBlock::Hourly)
+            .clear_excludes()
+            .build();
+
+        assert_eq!(
+            TimeMachineRequestBuilder::new(API_KEY, LAT, LONG, TIME).build(),
+            time_machine_request
+        );
+    }
+
+    #[test]
+    fn test_request_builders_dedupe_excludes() {
+        let mut blocks = vec![ExcludeBlock::Hourly, ExcludeBlock::Daily, ExcludeBlock::Alerts];
+
+        let forecast_request = ForecastRequestBuilder::new(API_KEY, LAT, LONG)
+            .exclude_block(ExcludeBlock::Hourly)
+            .exclude_blocks(&mut blocks.clone())
+            .build();
+
+        let time_machine_request = TimeMachineRequestBuilder::new(API_KEY, LAT, LONG, TIME)
+            .exclude_block(ExcludeBlock::Hourly)
+            .exclude_blocks(&mut blocks)
+            .build();
+
+        for url in &[forecast_request.url, time_machine_request.url] {
+            assert_eq!(Some("exclude=hourly%2Cdaily%2Calerts"), url.query());
+        }
+    }
+
+    #[test]
+    fn test_request_builders_exclude_all() {
+        let blocks = vec![ExcludeBlock::Daily, ExcludeBlock::Alerts];
+
+        let forecast_request = ForecastRequestBuilder::new(API_KEY, LAT, LONG)
+            .exclude_all(&blocks)
+            .build();
+
+        let time_machine_request = TimeMachineRequestBuilder::new(API_KEY, LAT, LONG, TIME)
+            .exclude_all(&blocks)
+            .build();
+
+        assert_eq!(blocks, forecast_request.exclude);
+        assert_eq!(blocks, time_machine_request.exclude);
+        assert_eq!(2, blocks.len());
+    }
+
+    #[test]
+    fn test_api_response_display() {
+        let response: ApiResponse = serde_json::from_str(
+            "{\"latitude\":0,\"longitude\":0,\"timezone\":\"America/Los_Angeles\",\"offset\":0,\
+              \"currently\":{\"time\":0,\"temperature\":14.2,\"summary\":\"Partly Cloudy\",\
+              \"precipProbability\":0.2,\"precipType\":\"rain\"},\
+              \"flags\":{\"sources\":[],\"units\":\"si\"}}"
+        ).unwrap();
+
+        assert_eq!(
+            "America/Los_Angeles: 14°C, Partly Cloudy, 20% chance of rain",
+            response.to_string()
+        );
+
+        let sparse: ApiResponse = serde_json::from_str(
+            "{\"latitude\":0,\"longitude\":0,\"timezone\":\"UTC\",\"offset\":0,\
+              \"currently\":{\"time\":0,\"temperature\":-3.6}}"
+        ).unwrap();
+
+        assert_eq!("UTC: -4°", sparse.to_string());
+    }
+
+    #[test]
+    fn test_uv_risk_from_index() {
+        assert_eq!(UvRisk::Low, UvRisk::from_index(-4.0));
+        assert_eq!(UvRisk::Low, UvRisk::from_index(2.4));
+        assert_eq!(UvRisk::Moderate, UvRisk::from_index(2.5));
+        assert_eq!(UvRisk::Moderate, UvRisk::from_index(5.0));
+        assert_eq!(UvRisk::High, UvRisk::from_index(7.0));
+        assert_eq!(UvRisk::VeryHigh, UvRisk::from_index(10.0));
+        assert_eq!(UvRisk::Extreme, UvRisk::from_index(11.0));
+        assert_eq!(UvRisk::Extreme, UvRisk::from_index(1000.0));
+        assert_eq!(UvRisk::Extreme, UvRisk::from_index(f64::INFINITY));
+
+        assert_eq!("Very High", UvRisk::VeryHigh.as_str());
+
+        assert_eq!(
+            Some(UvRisk::High),
+            data_point_from_json("{\"time\":0,\"uvIndex\":6}").uv_risk()
+        );
+        assert_eq!(None, data_point_from_json("{\"time\":0}").uv_risk());
+    }
+
+    fn assert_approx_eq(expected: f64, actual: Option<f64>) {
+        let actual = actual.unwrap();
+
+        assert!((expected - actual).abs() < 1e-9, "expected {}, got {}", expected, actual);
+    }
+
+    #[test]
+    fn test_data_point_wind_speed_conversions() {
+        let data_point = data_point_from_json("{\"time\":0,\"windSpeed\":36,\"windGust\":10}");
+
+        assert_approx_eq(10.0, data_point.wind_speed_ms(Units::CA));
+        assert_approx_eq(36.0, data_point.wind_speed_kmh(Units::CA));
+        assert_approx_eq(36.0, data_point.wind_speed_mph(Units::Imperial));
+        assert_approx_eq(16.09344, data_point.wind_speed_ms(Units::UK));
+        assert_approx_eq(10.0, data_point.wind_gust_ms(Units::SI));
+        assert_approx_eq(36.0, data_point.wind_gust_kmh(Units::SI));
+        assert_approx_eq(10.0, data_point.wind_gust_mph(Units::Imperial));
+        assert_eq!(None, data_point.wind_speed_ms(Units::Auto));
+        assert_eq!(None, data_point_from_json("{\"time\":0}").wind_gust_ms(Units::SI));
+
+        assert_eq!(Some("km/h"), Units::CA.speed_unit().map(|unit| unit.symbol()));
+    }
+
+    #[test]
+    fn test_beaufort_from_ms() {
+        assert_eq!(Beaufort::Calm, Beaufort::from_ms(0.0));
+        assert_eq!(Beaufort::LightAir, Beaufort::from_ms(0.5));
+        assert_eq!(Beaufort::Gale, Beaufort::from_ms(17.2));
+        assert_eq!(Beaufort::StrongGale, Beaufort::from_ms(24.4));
+        assert_eq!(Beaufort::HurricaneForce, Beaufort::from_ms(50.0));
+        assert_eq!(12, Beaufort::HurricaneForce.force());
+    }
+
+    #[test]
+    fn test_data_point_pressure_and_visibility_conversions() {
+        let data_point = data_point_from_json(
+            "{\"time\":0,\"pressure\":1013.25,\"visibility\":10}"
+        );
+
+        assert_approx_eq(1013.25, data_point.pressure_hpa());
+        assert!((29.92 - data_point.pressure_inhg().unwrap()).abs() < 0.01);
+        assert_approx_eq(10.0, data_point.visibility_km(Units::SI));
+        assert_approx_eq(16.09344, data_point.visibility_km(Units::UK));
+        assert_approx_eq(10.0, data_point.visibility_miles(Units::Imperial));
+        assert_eq!(None, data_point.visibility_miles(Units::Auto));
+        assert_eq!(None, data_point_from_json("{\"time\":0}").pressure_inhg());
+    }
+
+    #[test]
+    fn test_models_omit_absent_fields() {
+        let json = "{\"humidity\":0.5,\"temperatureMax\":12.0,\"time\":0}";
+
+        assert_eq!(json, serde_json::to_string(&data_point_from_json(json)).unwrap());
+
+        let json = "{\"latitude\":0.0,\"longitude\":0.0,\"timezone\":\"UTC\",\"offset\":0,\
+                    \"hourly\":{\"data\":[]},\"flags\":{\"sources\":[],\"units\":\"si\"}}";
+        let response: ApiResponse = serde_json::from_str(json).unwrap();
+
+        assert_eq!(json, serde_json::to_string(&response).unwrap());
+    }
+
+    #[test]
+    fn test_api_response_is_degraded() {
+        let response = |flags: &str| -> ApiResponse {
+            serde_json::from_str(&format!(
+                "{{\"latitude\":0,\"longitude\":0,\"timezone\":\"UTC\",\"offset\":0{}}}",
+                flags
+            )).unwrap()
+        };
+
+        assert!(!response("").is_degraded());
+        assert!(!response(",\"flags\":{\"sources\":[],\"units\":\"si\"}").is_degraded());
+        assert!(response(
+            ",\"flags\":{\"darksky-unavailable\":\"\",\"sources\":[],\"units\":\"si\"}"
+        ).is_degraded());
+    }
+
+    #[test]
+    fn test_flags_pirate_weather_fields() {
+        let json = "{\"nearest-station\":1.5,\"sources\":[\"gfs\"],\"units\":\"us\",\
+                    \"version\":\"V2.0\"}";
+        let flags: Flags = serde_json::from_str(json).unwrap();
+
+        assert_eq!(Some(1.5), flags.nearest_station);
+        assert_eq!(Some("V2.0".to_string()), flags.version);
+        assert_eq!(json, serde_json::to_string(&flags).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_models_capture_extra_fields() {
+        let json = "{\"latitude\":0.0,\"longitude\":0.0,\"timezone\":\"UTC\",\"offset\":0,\
+                    \"currently\":{\"time\":0,\"temperature\":1.0,\"cape\":2.5},\
+                    \"elevation\":10}";
+        let response: ApiResponse = serde_json::from_str(json).unwrap();
+        let currently = response.currently.as_ref().unwrap();
+
+        assert_eq!(Some(1.0), currently.temperature);
+        assert_eq!(Some(&serde_json::json!(2.5)), currently.extra.get("cape"));
+        assert!(!currently.extra.contains_key("temperature"));
+        assert_eq!(Some(&serde_json::json!(10)), response.extra.get("elevation"));
+
+        let round_tripped: ApiResponse = serde_json::from_str(
+            &serde_json::to_string(&response).unwrap()
+        ).unwrap();
+
+        assert_eq!(response, round_tripped);
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_data_point_accumulation_fields() {
+        let data_point = data_point_from_json(
+            "{\"time\":0,\"precipAccumulation\":4.0,\"liquidAccumulation\":1.0,\
+              \"snowAccumulation\":2.0,\"iceAccumulation\":0.5}"
+        );
+
+        assert_eq!(Some(4.0), data_point.precip_accumulation);
+        assert_eq!(Some(1.0), data_point.liquid_accumulation);
+        assert_eq!(Some(2.0), data_point.snow_accumulation);
+        assert_eq!(Some(0.5), data_point.ice_accumulation);
+        assert!(data_point.extra.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_data_point_fire_index_and_smoke() {
+        let data_point = data_point_from_json("{\"time\":0,\"fireIndex\":12.5,\"smoke\":3.0}");
+
+        assert_eq!(Some(12.5), data_point.fire_index);
+        assert_eq!(Some(3.0), data_point.smoke);
+        assert!(data_point.extra.is_empty());
+    }
+
+    #[test]
+    fn test_flags_without_units() {
+        let flags: Flags = serde_json::from_str(
+            "{\"darksky-unavailable\":\"\",\"sources\":[]}"
+        ).unwrap();
+
+        assert_eq!(None, flags.units);
+        assert!(flags.is_data_source_unavailable());
+    }
+
+    #[test]
+    fn test_icon_serde_unknown() {
+        let icons: Vec<Icon> = serde_json::from_str("[\"partly-cloudy-day\",\"smoke\"]").unwrap();
+
+        assert_eq!(vec![Icon::PartlyCloudyDay, Icon::Unknown("smoke".to_string())], icons);
+        assert_eq!(
+            "[\"partly-cloudy-day\",\"smoke\"]",
+            serde_json::to_string(&icons).unwrap()
+        );
+
+        let data_point = data_point_from_json("{\"time\":0,\"icon\":\"haze\"}");
+
+        assert_eq!(Some(Icon::Unknown("haze".to_string())), data_point.icon);
+    }
+
+    #[test]
+    fn test_precip_type_serde_other() {
+        let data_point = data_point_from_json("{\"time\":0,\"precipType\":\"hail\"}");
+
+        assert_eq!(Some(PrecipType::Other("hail".to_string())), data_point.precip_type);
+        assert_eq!(
+            "{\"precipType\":\"hail\",\"time\":0}",
+            serde_json::to_string(&data_point).unwrap()
+        );
+
+        let data_point = data_point_from_json("{\"time\":0,\"precipType\":\"snow\"}");
+
+        assert_eq!(Some(PrecipType::Snow), data_point.precip_type);
+    }
+
+    #[test]
+    fn test_api_response_all_data_points() {
+        let response: ApiResponse = serde_json::from_str(
+            "{\"latitude\":0,\"longitude\":0,\"timezone\":\"UTC\",\"offset\":0,\
+              \"currently\":{\"time\":1},\
+              \"hourly\":{\"data\":[{\"time\":2},{\"time\":3}]},\
+              \"daily\":{\"data\":[{\"time\":4}]}}"
+        ).unwrap();
+
+        let times: Vec<u64> = response.all_data_points().map(|d| d.time).collect();
+
+        assert_eq!(vec![1, 2, 3, 4], times);
+    }
+
+    fn data_block_from_json(json: &str) -> DataBlock {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_data_block_aggregations() {
+        let block = data_block_from_json(
+            "{\"data\":[{\"time\":0,\"temperature\":3.0},{\"time\":1},\
+                        {\"time\":2,\"temperature\":-1.0},{\"time\":3,\"temperature\":7.0}]}"
+        );
+
+        assert_eq!(Some(7.0), block.max_by_field(|d| d.temperature));
+        assert_eq!(Some(-1.0), block.min_by_field(|d| d.temperature));
+        assert_eq!(Some(3.0), block.average_field(|d| d.temperature));
+
+        assert_eq!(None, block.max_by_field(|d| d.humidity));
+        assert_eq!(None, block.min_by_field(|d| d.humidity));
+        assert_eq!(None, block.average_field(|d| d.humidity));
+
+        let empty = data_block_from_json("{\"data\":[]}");
+
+        assert_eq!(None, empty.average_field(|d| d.temperature));
+    }
+
+    #[test]
+    fn test_data_block_precip_onset_and_clearing() {
+        let block = data_block_from_json(
+            "{\"data\":[{\"time\":0,\"precipProbability\":0.1},{\"time\":60},\
+                        {\"time\":120,\"precipProbability\":0.6},\
+                        {\"time\":180,\"precipProbability\":0.8},\
+                        {\"time\":240,\"precipProbability\":0.2}]}"
+        );
+
+        assert_eq!(Some(120), block.next_precip_time(0.5));
+        assert_eq!(Some(240), block.precip_clears_at(0.5));
+        assert_eq!(Some(0), block.next_precip_time(0.0));
+        assert_eq!(Some(60), block.precip_clears_at(0.0));
+        assert_eq!(Some(180), block.next_precip_time(0.7));
+        assert_eq!(None, block.next_precip_time(0.9));
+        assert_eq!(None, block.precip_clears_at(0.9));
+        assert_eq!(None, block.precip_clears_at(0.15));
+    }
+
+    #[test]
+    fn test_api_response_current_getters() {
+        let response: ApiResponse = serde_json::from_str(
+            "{\"latitude\":0,\"longitude\":0,\"timezone\":\"UTC\",\"offset\":0,\
+              \"currently\":{\"time\":0,\"temperature\":21.5,\"summary\":\"Clear\",\
+              \"icon\":\"clear-day\"}}"
+        ).unwrap();
+
+        assert_eq!(Some(21.5), response.current_temperature());
+        assert_eq!(Some("Clear"), response.current_summary());
+        assert_eq!(Some(&Icon::ClearDay), response.current_icon());
+
+        let empty: ApiResponse = serde_json::from_str(
+            "{\"latitude\":0,\"longitude\":0,\"timezone\":\"UTC\",\"offset\":0}"
+        ).unwrap();
+
+        assert_eq!(None, empty.current_temperature());
+        assert_eq!(None, empty.current_summary());
+        assert_eq!(None, empty.current_icon());
+    }
+
+    // tests for the api client, using a fake transport
+
+    #[cfg(feature = "json")]
+    struct FakeTransport {
+        status: StatusCode,
+        body: &'static str
+    }
+
+    #[cfg(feature = "json")]
+    impl HttpTransport for FakeTransport {
+        fn get_with_headers(&self, _url: Url, _headers: HeaderMap) -> TransportFuture<'_> {
+            let response = http::Response::builder()
+                .status(self.status)
+                .body(self.body)
+                .unwrap();
+
+            Box::pin(async move { Ok(Response::from(response)) })
+        }
+    }
+
+    static FAKE_RESPONSE: &str =
+        "{\"latitude\":6.66,\"longitude\":66.6,\"timezone\":\"UTC\",\"offset\":0}";
+
+    #[tokio::test]
+    #[cfg(feature = "json")]
+    async fn test_api_client_fake_transport() {
+        let transport = FakeTransport { status: StatusCode::OK, body: FAKE_RESPONSE };
+        let api_client = ApiClient::new(&transport);
+
+        let request = ForecastRequestBuilder::new(API_KEY, LAT, LONG).build();
+
+        let response = api_client.get_forecast(&request).await.unwrap();
+
+        assert_eq!(StatusCode::OK, response.status());
+
+        let responses = api_client.get_forecasts(vec![request.clone(), request]).await;
+
+        assert_eq!(2, responses.len());
+        assert_eq!(LAT, responses[0].as_ref().unwrap().latitude);
+        assert_eq!(LONG, responses[1].as_ref().unwrap().longitude);
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "json")]
+    async fn test_api_client_fake_transport_errors() {
+        let request = ForecastRequestBuilder::new(API_KEY, LAT, LONG).build();
+
+        let unavailable = FakeTransport {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            body: FAKE_RESPONSE
+        };
+        let responses = ApiClient::new(&unavailable).get_forecasts(vec![request.clone()]).await;
+
+        assert!(matches!(responses[0], Err(ForecastError::Api(_))));
+
+        let malformed = FakeTransport { status: StatusCode::OK, body: "{" };
+        let responses = ApiClient::new(&malformed).get_forecasts(vec![request]).await;
+
+        assert!(matches!(
+            responses[0],
+            Err(ForecastError::Json(_)) | Err(ForecastError::Deserialize { .. })
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_api_response_from_json() {
+        let from_json = ApiResponse::from_json(FAKE_RESPONSE).unwrap();
+        let from_slice = ApiResponse::from_slice(FAKE_RESPONSE.as_bytes()).unwrap();
+
+        assert_eq!(LAT, from_json.latitude);
+        assert_eq!(from_json, from_slice);
+
+        assert!(matches!(
+            ApiResponse::from_json("[]"),
+            Err(ForecastError::Json(_)) | Err(ForecastError::Deserialize { .. })
+        ));
+    }
+
+    #[test]
+    fn test_api_client_builder_defaults() {
+        let reqwest_client = Client::new();
+
+        let api_client = ApiClientBuilder::new(&reqwest_client)
+            .default_units(Units::SI)
+            .default_lang(Lang::German)
+            .build();
+
+        assert_eq!(
+            ForecastRequestBuilder::new(API_KEY, LAT, LONG)
+                .units(Units::SI)
+                .lang(Lang::German)
+                .build(),
+            api_client.forecast_request(API_KEY, LAT, LONG).build()
+        );
+
+        assert_eq!(
+            TimeMachineRequestBuilder::new(API_KEY, LAT, LONG, TIME)
+                .units(Units::Imperial)
+                .lang(Lang::German)
+                .build(),
+            api_client.time_machine_request(API_KEY, LAT, LONG, TIME)
+                .units(Units::Imperial)
+                .build()
+        );
+
+        assert_eq!(
+            ForecastRequestBuilder::new(API_KEY, LAT, LONG).build(),
+            ApiClient::new(&reqwest_client).forecast_request(API_KEY, LAT, LONG).build()
+        );
+    }
+
+    #[test]
+    fn test_data_block_error_names_data_point() {
+        let err = serde_json::from_str::<DataBlock>(
+            "{\"data\":[{\"time\":0},{\"time\":1},{\"temperature\":2.0}]}"
+        ).unwrap_err();
+
+        assert!(err.to_string().contains("invalid data point at index 2"));
+        assert!(err.to_string().contains("missing field `time`"));
+    }
+
+    #[test]
+    fn test_forecast_request_builder_minimal() {
+        let request = ForecastRequestBuilder::new(API_KEY, LAT, LONG)
+            .exclude_block(ExcludeBlock::Flags)
+            .minimal()
+            .exclude_block(ExcludeBlock::Alerts)
+            .build();
+
+        assert_eq!(
+            vec![ExcludeBlock::Minutely, ExcludeBlock::Alerts, ExcludeBlock::Flags],
+            request.exclude
+        );
+    }
+
+    #[test]
+    fn test_request_builders_owned_api_key() {
+        fn build_owned(api_key: String) -> (ForecastRequest<'static>, TimeMachineRequest<'static>) {
+            (
+                ForecastRequestBuilder::new(api_key.clone(), LAT, LONG).build(),
+                TimeMachineRequestBuilder::new(api_key, LAT, LONG, TIME).build()
+            )
+        }
+
+        let (forecast_request, time_machine_request) = build_owned(API_KEY.to_string());
+
+        assert_eq!(ForecastRequestBuilder::new(API_KEY, LAT, LONG).build(), forecast_request);
+        assert_eq!(
+            TimeMachineRequestBuilder::new(API_KEY, LAT, LONG, TIME).build(),
+            time_machine_request
+        );
+    }
+
+    #[test]
+    fn test_sort_and_dedup_by_time() {
+        let mut data = data_block_from_json(
+            "{\"data\":[{\"time\":3},{\"time\":1,\"temperature\":1.0},{\"time\":2},\
+                        {\"time\":1,\"temperature\":2.0}]}"
+        ).data;
+
+        assert_eq!(Ordering::Greater, data[0].cmp_by_time(&data[1]));
+        assert_eq!(Ordering::Equal, data[1].cmp_by_time(&data[3]));
+
+        sort_and_dedup_by_time(&mut data);
+
+        let times: Vec<u64> = data.iter().map(|d| d.time).collect();
+
+        assert_eq!(vec![1, 2, 3], times);
+        assert_eq!(Some(1.0), data[0].temperature);
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_merge_hourly() {
+        let mut response = ApiResponse::from_json(
+            "{\"latitude\":42.3736,\"longitude\":-71.1097,\"timezone\":\"America/New_York\",\
+              \"offset\":-4,\"hourly\":{\"data\":[{\"time\":7200,\"temperature\":1.0},\
+              {\"time\":10800}]}}"
+        ).unwrap();
+
+        let other = ApiResponse::from_json(
+            "{\"latitude\":42.37361,\"longitude\":-71.1097,\"timezone\":\"America/New_York\",\
+              \"offset\":-4,\"hourly\":{\"data\":[{\"time\":3600},\
+              {\"time\":7200,\"temperature\":2.0}]}}"
+        ).unwrap();
+
+        response.merge_hourly(&other).unwrap();
+
+        let hourly = response.hourly.unwrap();
+        let times: Vec<u64> = hourly.data.iter().map(|d| d.time).collect();
+
+        assert_eq!(vec![3600, 7200, 10800], times);
+        assert_eq!(Some(1.0), hourly.data[1].temperature);
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_merge_hourly_location_mismatch() {
+        let mut response = ApiResponse::from_json(
+            "{\"latitude\":42.3736,\"longitude\":-71.1097,\"timezone\":\"America/New_York\",\
+              \"offset\":-4}"
+        ).unwrap();
+
+        let other = ApiResponse::from_json(
+            "{\"latitude\":40.7128,\"longitude\":-74.006,\"timezone\":\"America/New_York\",\
+              \"offset\":-4,\"hourly\":{\"data\":[{\"time\":3600}]}}"
+        ).unwrap();
+
+        let err = response.merge_hourly(&other).unwrap_err();
+
+        assert_eq!(LocationMismatchError {
+            expected: (42.3736, -71.1097),
+            found: (40.7128, -74.006)
+        }, err);
+        assert!(response.hourly.is_none());
+    }
+
+    #[test]
+    fn test_lang_and_units_query_strings() {
+        let request = ForecastRequestBuilder::new(API_KEY, LAT, LONG)
+            .lang(Lang::TraditionalChinese)
+            .units(Units::UK)
+            .build();
+
+        assert_eq!(Some("lang=zh-tw&units=uk2"), request.url.query());
+
+        let request = TimeMachineRequestBuilder::new(API_KEY, LAT, LONG, TIME)
+            .lang(Lang::IgpayAtinlay)
+            .build();
+
+        assert_eq!(Some("lang=x-pig-latin"), request.url.query());
+    }
+
+    #[test]
+    fn test_as_query_str_matches_serde() {
+        for lang in Lang::all() {
+            assert_eq!(
+                serde_json::to_string(lang).unwrap(),
+                format!("\"{}\"", lang.as_query_str())
+            );
+        }
+
+        for units in Units::all() {
+            assert_eq!(
+                serde_json::to_string(units).unwrap(),
+                format!("\"{}\"", units.as_query_str())
+            );
+        }
+    }
+
+    #[test]
+    fn test_exclude_block_from_str() {
+        let blocks: Result<Vec<ExcludeBlock>, _> =
+            "hourly,daily".split(',').map(str::parse).collect();
+
+        assert_eq!(Ok(vec![ExcludeBlock::Hourly, ExcludeBlock::Daily]), blocks);
+
+        for block in &ExcludeBlock::all() {
+            assert_eq!(Ok(block.clone()), block.as_query_str().parse());
+            assert_eq!(
+                serde_json::to_string(block).unwrap(),
+                format!("\"{}\"", block.as_query_str())
+            );
+        }
+
+        let err = "weekly".parse::<ExcludeBlock>().unwrap_err();
+
+        assert_eq!(ParseCodeError { kind: "exclude block", code: "weekly".to_string() }, err);
+        assert_eq!("unknown exclude block \"weekly\"", err.to_string());
+    }
+
+    #[test]
+    fn test_lang_from_str() {
+        assert_eq!(Ok(Lang::French), "fr".parse());
+        assert_eq!(Ok(Lang::TraditionalChinese), "zh-tw".parse());
+        assert_eq!(Ok(Lang::NorwegianBokmal), "nb".parse());
+        assert_eq!(Ok(Lang::NorwegianBokmal), "no".parse());
+
+        for lang in Lang::all() {
+            assert_eq!(Ok(lang.clone()), lang.as_query_str().parse());
+        }
+
+        assert_eq!(
+            "unknown language \"klingon\"",
+            "klingon".parse::<Lang>().unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn test_units_from_str() {
+        for units in Units::all() {
+            assert_eq!(Ok(*units), units.as_query_str().parse());
+        }
+
+        assert_eq!(
+            Err(ParseCodeError { kind: "units", code: "uk".to_string() }),
+            "uk".parse::<Units>()
+        );
+    }
+
+    #[test]
+    fn test_lang_all() {
+        let langs = Lang::all();
+
+        assert_eq!(56, langs.len());
+        assert_eq!(langs.len(), langs.iter().map(Lang::as_query_str).unique().count());
+        assert_eq!("French", Lang::French.display_name());
+        assert_eq!("Simplified Chinese", Lang::SimplifiedChinese.display_name());
+        assert_eq!(5, Units::all().len());
+    }
+
+    #[test]
+    fn test_forecast_request_builder_try_build() {
+        let request = ForecastRequestBuilder::new(API_KEY, LAT, LONG)
+            .exclude_block(ExcludeBlock::Daily)
+            .extend(ExtendBy::Hourly)
+            .try_build()
+            .unwrap();
+
+        assert_eq!(Some(ExtendBy::Hourly), request.extend);
+
+        let err = ForecastRequestBuilder::new(API_KEY, LAT, LONG)
+            .exclude_block(ExcludeBlock::Hourly)
+            .extend(ExtendBy::Hourly)
+            .try_build()
+            .unwrap_err();
+
+        match err {
+            ForecastError::InvalidRequest(ref reason) => {
+                assert_eq!("cannot extend the hourly block because it is excluded", reason)
+            },
+            _ => panic!("expected an invalid request error, got {:?}", err)
+        }
+    }
+
+    #[cfg(feature = "json")]
+    static STREAMING_RESPONSE: &str =
+        "{\"latitude\":6.66,\"longitude\":66.6,\"timezone\":\"UTC\",\"offset\":0,\
+          \"minutely\":{\"data\":[{\"time\":60}]},\
+          \"hourly\":{\"summary\":\"Rain \\\"[data]\\\" {\",\"data\":[\
+            {\"time\":3600,\"summary\":\"}]\"},\
+            {\"time\":7200,\"temperature\":-1.5,\"extra\":{\"data\":[{\"time\":0}]}},\
+            {\"time\":\"invalid\"}\
+          ]},\
+          \"daily\":{\"data\":[{\"time\":86400}]}}";
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_hourly_data_scanner() {
+        let mut scanner = HourlyDataScanner::new();
+
+        let data_points: Vec<Result<DataPoint, ForecastError>> = STREAMING_RESPONSE
+            .as_bytes()
+            .chunks(3)
+            .flat_map(|chunk| scanner.feed(chunk))
+            .collect();
+
+        assert_eq!(3, data_points.len());
+        assert_eq!(3600, data_points[0].as_ref().unwrap().time);
+        assert_eq!(Some("}]"), data_points[0].as_ref().unwrap().summary.as_deref());
+        assert_eq!(7200, data_points[1].as_ref().unwrap().time);
+        assert_eq!(Some(-1.5), data_points[1].as_ref().unwrap().temperature);
+        assert!(data_points[2].is_err());
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "json")]
+    async fn test_api_client_get_forecast_streaming() {
+        let transport = FakeTransport { status: StatusCode::OK, body: STREAMING_RESPONSE };
+        let api_client = ApiClient::new(&transport);
+
+        let request = ForecastRequestBuilder::new(API_KEY, LAT, LONG).build();
+
+        let data_points: Vec<Result<DataPoint, ForecastError>> = api_client
+            .get_forecast_streaming(&request)
+            .await
+            .unwrap()
+            .collect()
+            .await;
+
+        let times: Vec<u64> = data_points.iter()
+            .filter_map(|data_point| data_point.as_ref().ok())
+            .map(|data_point| data_point.time)
+            .collect();
+
+        assert_eq!(vec![3600, 7200], times);
+        assert_eq!(3, data_points.len());
+    }
+
+    #[test]
+    fn test_request_urls() {
+        let builder = ForecastRequestBuilder::new(API_KEY, LAT, LONG).units(Units::SI);
+        let url = builder.to_url();
+
+        assert_eq!(Some("units=si"), url.query());
+        assert_eq!(&url, builder.build().url());
+
+        let builder = TimeMachineRequestBuilder::new(API_KEY, LAT, LONG, TIME);
+
+        assert_eq!(builder.to_url(), builder.build().url);
+    }
+
+    #[test]
+    fn test_request_accessors() {
+        let request = ForecastRequestBuilder::new(API_KEY, LAT, LONG)
+            .exclude_block(ExcludeBlock::Daily)
+            .extend(ExtendBy::Hourly)
+            .lang(Lang::French)
+            .build();
+
+        assert_eq!(API_KEY, request.api_key());
+        assert_eq!(LAT, request.latitude());
+        assert_eq!(LONG, request.longitude());
+        assert_eq!(&[ExcludeBlock::Daily], request.exclude());
+        assert_eq!(Some(&ExtendBy::Hourly), request.extend());
+        assert_eq!(Some(&Lang::French), request.lang());
+        assert_eq!(None, request.units());
+
+        let request = TimeMachineRequestBuilder::new(API_KEY, LAT, LONG, TIME)
+            .units(Units::CA)
+            .build();
+
+        assert_eq!(API_KEY, request.api_key());
+        assert_eq!(LAT, request.latitude());
+        assert_eq!(LONG, request.longitude());
+        assert_eq!(TIME, request.time());
+        assert!(request.exclude().is_empty());
+        assert_eq!(None, request.lang());
+        assert_eq!(Some(&Units::CA), request.units());
+        assert_eq!(&request.url, request.url());
+    }
+
+    #[test]
+    fn test_forecast_request_builder_with_defaults() {
+        let defaults = ForecastRequestBuilder::with_defaults(API_KEY);
+
+        let requests: Vec<ForecastRequest> = [(LAT, LONG), (LONG, LAT)]
+            .iter()
+            .map(|&(lat, long)| defaults.clone().with_coordinates(lat, long).build())
+            .collect();
+
+        assert_eq!(
+            ForecastRequestBuilder::new(API_KEY, LAT, LONG)
+                .units(Units::SI)
+                .lang(Lang::English)
+                .build(),
+            requests[0]
+        );
+        assert_eq!(LONG, requests[1].latitude());
+        assert_eq!(LAT, requests[1].longitude());
+        assert!(requests[1].exclude().is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_api_response_validate() {
+        assert_eq!(Ok(()), ApiResponse::from_json(FAKE_RESPONSE).unwrap().validate());
+
+        let response = ApiResponse::from_json(
+            "{\"latitude\":91.0,\"longitude\":66.6,\"timezone\":\"UTC\",\"offset\":0,\
+              \"currently\":{\"time\":0,\"humidity\":1.5},\
+              \"hourly\":{\"data\":[{\"time\":7200,\"cloudCover\":0.5},\
+                                    {\"time\":3600,\"precipProbability\":-0.1}]}}"
+        ).unwrap();
+
+        let issues = response.validate().unwrap_err();
+
+        let rendered: Vec<String> = issues.iter().map(ValidationIssue::to_string).collect();
+
+        assert_eq!(
+            vec![
+                "response.latitude: 91 is not between -90 and 90",
+                "currently.humidity: 1.5 is not between 0 and 1",
+                "hourly.data[1].precip_probability: -0.1 is not between 0 and 1",
+                "hourly.data[1].time: 3600 is not after the preceding data point's time 7200"
+            ],
+            rendered
+        );
+        assert_eq!(Some(1), issues[2].index);
+        assert_eq!("hourly", issues[2].block);
+    }
+
+    #[test]
+    fn test_data_point_normalized() {
+        let data_point = data_point_from_json(
+            "{\"time\":0,\"cloudCover\":1.02,\"humidity\":0.5,\"precipProbability\":-0.01,\
+              \"temperature\":1.5}"
+        );
+
+        let normalized = data_point.normalized();
+
+        assert_eq!(Some(1.0), normalized.cloud_cover);
+        assert_eq!(Some(0.5), normalized.humidity);
+        assert_eq!(Some(0.0), normalized.precip_probability);
+        assert_eq!(Some(1.5), normalized.temperature);
+        assert_eq!(Some(1.02), data_point.cloud_cover);
+    }
+
+    #[test]
+    fn test_data_point_feels_like() {
+        let data_point = data_point_from_json(
+            "{\"time\":0,\"temperature\":50.0,\"apparentTemperature\":45.5}"
+        );
+
+        assert_eq!(Some((45.5, TempScale::Fahrenheit)), data_point.feels_like(Units::Imperial));
+        assert_eq!(None, data_point.feels_like(Units::Auto));
+
+        let data_point = data_point_from_json("{\"time\":0,\"temperature\":10.0}");
+
+        assert_eq!(Some((10.0, TempScale::Celsius)), data_point.feels_like(Units::SI));
+        assert_eq!(None, data_point_from_json("{\"time\":0}").feels_like(Units::SI));
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_api_response_resolved_units() {
+        assert_eq!(None, ApiResponse::from_json(FAKE_RESPONSE).unwrap().resolved_units());
+
+        let response = ApiResponse::from_json(
+            "{\"latitude\":6.66,\"longitude\":66.6,\"timezone\":\"UTC\",\"offset\":0,\
+              \"flags\":{\"sources\":[],\"units\":\"ca\"}}"
+        ).unwrap();
+
+        assert_eq!(Some(&Units::CA), response.resolved_units());
+    }
+
+    #[test]
+    #[cfg(feature = "verbose-errors")]
+    fn test_api_response_verbose_errors() {
+        let err = ApiResponse::from_json(
+            "{\"latitude\":6.66,\"longitude\":66.6,\"timezone\":\"UTC\",\"offset\":0,\
+              \"hourly\":{\"data\":[{\"time\":0},{\"time\":3600,\"temperature\":\"hot\"}]}}"
+        ).unwrap_err();
+
+        match err {
+            ForecastError::Deserialize { ref path, .. } => {
+                assert_eq!("hourly.data[1].temperature", path)
+            },
+            _ => panic!("expected a deserialize error, got {:?}", err)
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_api_response_to_json() {
+        let response = ApiResponse::from_json(FAKE_RESPONSE).unwrap();
+
+        assert_eq!(FAKE_RESPONSE, response.to_json().unwrap());
+
+        let pretty = response.to_json_pretty().unwrap();
+
+        assert!(pretty.starts_with("{\n  \"latitude\": 6.66,\n"));
+        assert_eq!(response, ApiResponse::from_json(&pretty).unwrap());
+    }
+
+    #[test]
+    fn test_data_point_comfort_level() {
+        let data_point = data_point_from_json("{\"time\":0,\"dewPoint\":17.0,\"humidity\":0.634}");
+
+        assert_eq!(Some(Comfort::Sticky), data_point.comfort_level(Units::SI));
+        assert_eq!(Some(Comfort::Dry), data_point.comfort_level(Units::Imperial));
+        assert_eq!(None, data_point.comfort_level(Units::Auto));
+        assert_eq!(Some(63.0), data_point.relative_humidity_percent());
+
+        assert_eq!(Comfort::Comfortable, Comfort::from_dew_point(10.0, TempScale::Celsius));
+        assert_eq!(Comfort::Oppressive, Comfort::from_dew_point(65.0, TempScale::Fahrenheit));
+        assert_eq!("Oppressive", Comfort::Oppressive.as_str());
+    }
+
+    #[test]
+    fn test_request_builders_query_param() {
+        let request = ForecastRequestBuilder::new(API_KEY, LAT, LONG)
+            .query_param("tmextra", "1")
+            .units(Units::SI)
+            .query_param("a b", "c&d")
+            .build();
+
+        assert_eq!(Some("units=si&tmextra=1&a+b=c%26d"), request.url().query());
+
+        let request = TimeMachineRequestBuilder::new(API_KEY, LAT, LONG, TIME)
+            .query_param("version", "2")
+            .build();
+
+        assert_eq!(Some("version=2"), request.url().query());
+    }
+
+    #[test]
+    fn test_request_builders_version() {
+        let request = ForecastRequestBuilder::new(API_KEY, LAT, LONG)
+            .query_param("tmextra", "1")
+            .version(2)
+            .build();
+
+        assert_eq!(Some("version=2&tmextra=1"), request.url().query());
+
+        let request = TimeMachineRequestBuilder::new(API_KEY, LAT, LONG, TIME)
+            .units(Units::CA)
+            .version(2)
+            .build();
+
+        assert_eq!(Some("units=ca&version=2"), request.url().query());
+    }
+
+    #[test]
+    fn test_requests_to_reqwest() {
+        let reqwest_client = Client::new();
+
+        let request = ForecastRequestBuilder::new(API_KEY, LAT, LONG).build();
+        let reqwest_request = request.to_reqwest(&reqwest_client).build().unwrap();
+
+        assert_eq!(reqwest::Method::GET, reqwest_request.method());
+        assert_eq!(request.url(), reqwest_request.url());
+
+        let request = TimeMachineRequestBuilder::new(API_KEY, LAT, LONG, TIME).build();
+        let reqwest_request = request.to_reqwest(&reqwest_client).build().unwrap();
+
+        assert_eq!(request.url(), reqwest_request.url());
+    }
+
+    #[test]
+    #[cfg(feature = "tracing")]
+    fn test_redacted_url() {
+        let request = ForecastRequestBuilder::new(API_KEY, LAT, LONG).units(Units::SI).build();
+
+        let redacted = redacted_url(API_KEY, request.url());
+
+        assert!(!redacted.contains(API_KEY));
+        assert_eq!(request.url().as_str().replace(API_KEY, "REDACTED"), redacted);
+    }
+
+    #[test]
+    fn test_data_block_total_precip_accumulation() {
+        let hourly = data_block_from_json(
+            "{\"data\":[{\"time\":0,\"precipIntensity\":2.0},\
+                        {\"time\":3600,\"precipAccumulation\":0.5,\"precipIntensity\":9.0},\
+                        {\"time\":7200},\
+                        {\"time\":10800,\"precipIntensity\":1.0}]}"
+        );
+
+        assert_approx_eq(0.2 + 0.5 + 0.1, hourly.total_precip_accumulation(Units::SI));
+        assert_approx_eq(2.0 + 0.5 + 1.0, hourly.total_precip_accumulation(Units::Imperial));
+        assert_eq!(None, hourly.total_precip_accumulation(Units::Auto));
+
+        let minutely = data_block_from_json(
+            "{\"data\":[{\"time\":0,\"precipIntensity\":6.0},\
+                        {\"time\":60,\"precipIntensity\":6.0}]}"
+        );
+
+        assert_approx_eq(0.02, minutely.total_precip_accumulation(Units::SI));
+        assert_eq!(None, data_block_from_json("{\"data\":[{\"time\":0}]}")
+            .total_precip_accumulation(Units::SI));
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_api_response_is_daylight() {
+        let response = ApiResponse::from_json(
+            "{\"latitude\":6.66,\"longitude\":66.6,\"timezone\":\"UTC\",\"offset\":0,\
+              \"daily\":{\"data\":[{\"time\":0,\"sunriseTime\":21600,\"sunsetTime\":64800},\
+                                   {\"time\":86400,\"sunriseTime\":172000},\
+                                   {\"time\":172800}]}}"
+        ).unwrap();
+
+        assert_eq!(Some(false), response.is_daylight(21599));
+        assert_eq!(Some(true), response.is_daylight(21600));
+        assert_eq!(Some(false), response.is_daylight(64800));
+        assert_eq!(Some(false), response.is_daylight(100_000));
+        assert_eq!(Some(true), response.is_daylight(172_000));
+        assert_eq!(None, response.is_daylight(172_800));
+        assert_eq!(None, response.is_daylight(259_200));
+        assert_eq!(None, ApiResponse::from_json(FAKE_RESPONSE).unwrap().is_daylight(0));
+
+        let last_day = ApiResponse::from_json(
+            "{\"latitude\":6.66,\"longitude\":66.6,\"timezone\":\"UTC\",\"offset\":0,\
+              \"daily\":{\"data\":[{\"time\":18446744073709551000,\
+                                    \"sunriseTime\":18446744073709551100}]}}"
+        ).unwrap();
+
+        assert_eq!(Some(true), last_day.is_daylight(u64::MAX - 1));
+    }
+
+    #[derive(Default)]
+    struct HeaderRecordingTransport {
+        headers: Mutex<Vec<HeaderMap>>
+    }
+
+    impl HttpTransport for HeaderRecordingTransport {
+        fn get_with_headers(&self, _url: Url, headers: HeaderMap) -> TransportFuture<'_> {
+            self.headers.lock().unwrap().push(headers);
+
+            let response = http::Response::builder()
+                .header(LAST_MODIFIED, "Sun, 06 Nov 1994 08:49:37 GMT")
+                .body(FAKE_RESPONSE)
+                .unwrap();
+
+            Box::pin(async { Ok(Response::from(response)) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_api_client_user_agent() {
+        let transport = HeaderRecordingTransport::default();
+        let request = ForecastRequestBuilder::new(API_KEY, LAT, LONG).build();
+
+        ApiClient::new(&transport).get_forecast(&request).await.unwrap();
+        ApiClientBuilder::new(&transport)
+            .user_agent("my-app/1.0")
+            .build()
+            .get_forecast(&request)
+            .await
+            .unwrap();
+
+        let headers = transport.headers.lock().unwrap();
+
+        assert_eq!(
+            format!("forecast-rs/{}", env!("CARGO_PKG_VERSION")),
+            headers[0][USER_AGENT].to_str().unwrap()
+        );
+        assert_eq!("my-app/1.0", headers[1][USER_AGENT]);
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "json")]
+    async fn test_caching_api_client_with_client() {
+        let transport = HeaderRecordingTransport::default();
+        let request = ForecastRequestBuilder::new(API_KEY, LAT, LONG).build();
+        let api_client = ApiClientBuilder::new(&transport).user_agent("my-app/1.0").build();
+        let caching_client = CachingApiClient::with_client(api_client, Duration::from_secs(60));
+
+        caching_client.get_forecast(&request).await.unwrap();
+
+        assert_eq!(Duration::from_secs(60), caching_client.ttl());
+        assert_eq!("my-app/1.0", transport.headers.lock().unwrap()[0][USER_AGENT]);
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "json")]
+    async fn test_api_client_get_forecast_conditional() {
+        let request = ForecastRequestBuilder::new(API_KEY, LAT, LONG).build();
+
+        let transport = HeaderRecordingTransport::default();
+        let api_client = ApiClient::new(&transport);
+
+        let last_modified = match api_client.get_forecast_conditional(&request, None).await {
+            Ok(ConditionalResponse::Modified(response, Some(last_modified))) => {
+                assert_eq!(LAT, response.latitude);
+                last_modified
+            },
+            other => panic!("expected a modified response, got {:?}", other)
+        };
+
+        api_client.get_forecast_conditional(&request, Some(last_modified)).await.unwrap();
+
+        {
+            let headers = transport.headers.lock().unwrap();
+
+            assert!(!headers[0].contains_key(IF_MODIFIED_SINCE));
+            assert_eq!("Sun, 06 Nov 1994 08:49:37 GMT", headers[1][IF_MODIFIED_SINCE]);
+        }
+
+        let not_modified = FakeTransport { status: StatusCode::NOT_MODIFIED, body: "" };
+        let response = ApiClient::new(&not_modified)
+            .get_forecast_conditional(&request, Some(last_modified))
+            .await
+            .unwrap();
+
+        assert_eq!(ConditionalResponse::NotModified, response);
+    }
+
+    // Records the `kind` of every error counted while it is the local
+    // recorder. The `metrics` macros register a counter each time they
+    // increment one, so registrations stand in for increments.
+    #[cfg(all(feature = "json", feature = "metrics"))]
+    #[derive(Default)]
+    struct ErrorKindRecorder {
+        kinds: Mutex<Vec<String>>
+    }
+
+    #[cfg(all(feature = "json", feature = "metrics"))]
+    impl metrics::Recorder for ErrorKindRecorder {
+        fn describe_counter(&self, _: metrics::KeyName, _: Option<metrics::Unit>,
+                            _: metrics::SharedString) {}
+
+        fn describe_gauge(&self, _: metrics::KeyName, _: Option<metrics::Unit>,
+                          _: metrics::SharedString) {}
+
+        fn describe_histogram(&self, _: metrics::KeyName, _: Option<metrics::Unit>,
+                              _: metrics::SharedString) {}
+
+        fn register_counter(&self, key: &metrics::Key, _: &metrics::Metadata<'_>)
+            -> metrics::Counter {
+            if key.name() == "forecast_request_errors_total" {
+                let kind = key.labels().find(|label| label.key() == "kind").unwrap();
+
+                self.kinds.lock().unwrap().push(kind.value().to_string());
+            }
+
+            metrics::Counter::noop()
+        }
+
+        fn register_gauge(&self, _: &metrics::Key, _: &metrics::Metadata<'_>) -> metrics::Gauge {
+            metrics::Gauge::noop()
+        }
+
+        fn register_histogram(&self, _: &metrics::Key, _: &metrics::Metadata<'_>)
+            -> metrics::Histogram {
+            metrics::Histogram::noop()
+        }
+    }
+
+    #[cfg(all(feature = "json", feature = "metrics"))]
+    fn recorded_error_kinds(status: StatusCode, body: &'static str) -> Vec<String> {
+        let recorder = ErrorKindRecorder::default();
+        let request = ForecastRequestBuilder::new(API_KEY, LAT, LONG).build();
+        let transport = FakeTransport { status, body };
+        let api_client = ApiClient::new(&transport);
+
+        metrics::with_local_recorder(&recorder, || {
+            futures::executor::block_on(api_client.get_forecast_conditional(&request, None))
+        }).ok();
+
+        recorder.kinds.into_inner().unwrap()
+    }
+
+    #[test]
+    #[cfg(all(feature = "json", feature = "metrics"))]
+    fn test_metrics_not_modified_is_not_an_error() {
+        assert!(recorded_error_kinds(StatusCode::NOT_MODIFIED, "").is_empty());
+        assert!(recorded_error_kinds(StatusCode::OK, FAKE_RESPONSE).is_empty());
+        assert_eq!(vec!["status"], recorded_error_kinds(StatusCode::INTERNAL_SERVER_ERROR, ""));
+    }
+
+    #[test]
+    #[cfg(all(feature = "json", feature = "metrics"))]
+    fn test_metrics_conditional_json_error() {
+        assert_eq!(vec!["json"], recorded_error_kinds(StatusCode::OK, "not json"));
+    }
+
+    #[test]
+    fn test_data_point_is_severe() {
+        let thunderstorm = data_point_from_json("{\"time\":0,\"icon\":\"thunderstorm\"}");
+
+        assert!(thunderstorm.is_severe(Units::Auto));
+        assert!(!data_point_from_json("{\"time\":0,\"icon\":\"rain\"}").is_severe(Units::SI));
+
+        let heavy_rain = data_point_from_json("{\"time\":0,\"precipIntensity\":0.3}");
+
+        assert!(heavy_rain.is_severe(Units::Imperial));
+        assert!(!heavy_rain.is_severe(Units::SI));
+        assert!(!heavy_rain.is_severe(Units::Auto));
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_api_response_has_severe_conditions() {
+        assert!(!ApiResponse::from_json(FAKE_RESPONSE).unwrap().has_severe_conditions());
+
+        let heavy_rain = ApiResponse::from_json(
+            "{\"latitude\":6.66,\"longitude\":66.6,\"timezone\":\"UTC\",\"offset\":0,\
+              \"currently\":{\"time\":0,\"precipIntensity\":8.0},\
+              \"flags\":{\"sources\":[],\"units\":\"si\"}}"
+        ).unwrap();
+
+        assert!(heavy_rain.has_severe_conditions());
+
+        let mut warned = ApiResponse::from_json(FAKE_RESPONSE).unwrap();
+
+        warned.alerts = Some(vec![alert(Severity::Watch, 0, 0)]);
+        assert!(!warned.has_severe_conditions());
+
+        warned.alerts = Some(vec![alert(Severity::Watch, 0, 0), alert(Severity::Warning, 0, 0)]);
+        assert!(warned.has_severe_conditions());
+    }
+
+    #[test]
+    fn test_forecast_request_same_location_and_params() {
+        let request = ForecastRequestBuilder::new(API_KEY, LAT, LONG)
+            .units(Units::SI)
+            .version(2)
+            .build();
+
+        let rotated = ForecastRequestBuilder::new("another_api_key", LAT, LONG)
+            .units(Units::SI)
+            .version(2)
+            .build();
+
+        assert_ne!(request, rotated);
+        assert!(request.same_location_and_params(&rotated));
+
+        let other_units = ForecastRequestBuilder::new(API_KEY, LAT, LONG)
+            .units(Units::Imperial)
+            .version(2)
+            .build();
+
+        assert!(!request.same_location_and_params(&other_units));
+
+        let other_params = ForecastRequestBuilder::new(API_KEY, LAT, LONG)
+            .units(Units::SI)
+            .build();
+
+        assert!(!request.same_location_and_params(&other_params));
+    }
+
+    #[test]
+    #[cfg(feature = "olc")]
+    fn test_forecast_request_builder_from_plus_code() {
+        let request = ForecastRequestBuilder::from_plus_code(API_KEY, "87JC9W76+HV")
+            .unwrap()
+            .build();
+
+        assert!((request.latitude() - 42.36395).abs() < 1e-4);
+        assert!((request.longitude() - -71.08789).abs() < 1e-4);
+
+        for code in &["9W76+HV", "not a code"] {
+            match ForecastRequestBuilder::from_plus_code(API_KEY, code) {
+                Err(ForecastError::InvalidRequest(_)) => {},
+                other => panic!("expected an invalid request, got {:?}", other)
+            }
+        }
+    }
+
+    #[test]
+    fn test_units_conventions() {
+        assert!(Units::SI.is_metric());
+        assert!(Units::CA.is_metric());
+        assert!(Units::UK.is_metric());
+        assert!(!Units::Imperial.is_metric());
+        assert!(!Units::Auto.is_metric());
+
+        assert_eq!(Some(TempScale::Celsius), Units::UK.temperature_scale());
+        assert_eq!(Some(SpeedUnit::MilesPerHour), Units::UK.speed_unit());
+        assert_eq!(Some(SpeedUnit::KilometresPerHour), Units::CA.speed_unit());
+        assert_eq!(Some(SpeedUnit::MetresPerSecond), Units::SI.speed_unit());
+        assert_eq!(None, Units::Auto.speed_unit());
+        assert_eq!("mph", SpeedUnit::MilesPerHour.symbol());
+    }
+
+    #[test]
+    fn test_units_for_country() {
+        assert_eq!(Units::Imperial, Units::for_country("US"));
+        assert_eq!(Units::UK, Units::for_country("gb"));
+        assert_eq!(Units::CA, Units::for_country("Ca"));
+        assert_eq!(Units::SI, Units::for_country("DE"));
+        assert_eq!(Units::SI, Units::for_country(""));
+    }
+
+    #[test]
+    fn test_pirate_weather_lang_serde() {
+        let langs = [
+            (Lang::Bengali, "bn"),
+            (Lang::Welsh, "cy"),
+            (Lang::Esperanto, "eo"),
+            (Lang::Persian, "fa"),
+            (Lang::Irish, "ga"),
+            (Lang::ScottishGaelic, "gd"),
+            (Lang::Hebrew, "he"),
+            (Lang::Hindi, "hi"),
+            (Lang::Kannada, "kn"),
+            (Lang::Latvian, "lv"),
+            (Lang::Malayalam, "ml"),
+            (Lang::Marathi, "mr"),
+            (Lang::Punjabi, "pa"),
+            (Lang::Tamil, "ta"),
+            (Lang::Telugu, "te"),
+            (Lang::Urdu, "ur")
+        ];
+
+        for &(ref lang, code) in langs.iter() {
+            let json = format!("\"{}\"", code);
+
+            assert_eq!(json, serde_json::to_string(lang).unwrap());
+            assert_eq!(*lang, serde_json::from_str::<Lang>(&json).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_czech_lang_alias() {
+        assert_eq!(Lang::Czech, serde_json::from_str::<Lang>("\"cz\"").unwrap());
+        assert_eq!(Lang::Czech, serde_json::from_str::<Lang>("\"cs\"").unwrap());
+        assert_eq!(Ok(Lang::Czech), "cz".parse());
+        assert_eq!("\"cs\"", serde_json::to_string(&Lang::Czech).unwrap());
+    }
+
+    #[test]
+    fn test_forecast_request_cache_key() {
+        let request = ForecastRequestBuilder::new(API_KEY, LAT, LONG)
+            .units(Units::SI)
+            .lang(Lang::French)
+            .build();
+
+        assert_eq!("6.6600,66.6000?lang=fr&units=si", request.cache_key());
+        assert!(!request.cache_key().contains(API_KEY));
+
+        let nearby = ForecastRequestBuilder::new("another_api_key", LAT + 0.00001, LONG)
+            .units(Units::SI)
+            .lang(Lang::French)
+            .build();
+
+        assert_eq!(request.cache_key(), nearby.cache_key());
+
+        let distinct = ForecastRequestBuilder::new(API_KEY, LAT + 0.0001, LONG)
+            .units(Units::SI)
+            .lang(Lang::French)
+            .build();
+
+        assert_ne!(request.cache_key(), distinct.cache_key());
+
+        assert_eq!(
+            "0.0000,0.0000",
+            ForecastRequestBuilder::new(API_KEY, -0.00001, 0.0).build().cache_key()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "test-fixtures")]
+    fn test_fixtures() {
+        let full = fixtures::sample_forecast_response();
+
+        assert!(full.currently.is_some());
+        assert!(full.minutely.is_some());
+        assert!(full.hourly.is_some());
+        assert!(full.daily.is_some());
+        assert!(full.alerts.is_some());
+        assert_eq!(Some(&Units::Imperial), full.resolved_units());
+
+        let currently_only = fixtures::sample_currently_only_response();
+
+        assert!(currently_only.currently.is_some());
+        assert!(currently_only.hourly.is_none());
+        assert!(currently_only.daily.is_none());
+
+        let unavailable = fixtures::sample_darksky_unavailable_response();
+
+        assert!(unavailable.flags.unwrap().is_data_source_unavailable());
+    }
+
+    #[test]
+    fn test_data_block_between() {
+        let block: DataBlock = serde_json::from_str(
+            "{\"data\":[{\"time\":0},{\"time\":3600},{\"time\":7200},{\"time\":10800}]}"
+        ).unwrap();
+
+        let times = |points: Vec<&DataPoint>| points.iter().map(|d| d.time).collect::<Vec<_>>();
+
+        assert_eq!(vec![3600, 7200], times(block.between(3600, 10800)));
+        assert_eq!(vec![0, 3600], times(block.between(0, 3601)));
+        assert!(block.between(20000, 30000).is_empty());
+        assert!(block.between(7200, 3600).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_api_response_hourly_window() {
+        let mut response = ApiResponse::from_json(FAKE_RESPONSE).unwrap();
+
+        response.hourly = None;
+        assert!(response.hourly_window(0, u64::MAX).is_empty());
+
+        response.hourly = Some(serde_json::from_str(
+            "{\"data\":[{\"time\":0},{\"time\":3600},{\"time\":7200}]}"
+        ).unwrap());
+
+        let window = response.hourly_window(3600, 7201);
+
+        assert_eq!(2, window.len());
+        assert_eq!(3600, window[0].time);
+    }
+
+    // Every field of `DataPoint` should be read from its camelCase property,
+    // leaving nothing unmatched in `extra`.
+    #[test]
+    #[cfg(feature = "json")]
+    #[allow(deprecated)]
+    fn test_data_point_deserializes_every_field() {
+        let data_point = data_point_from_json(
+            "{\"apparentTemperature\":1,\"apparentTemperatureHigh\":2,\
+             \"apparentTemperatureHighTime\":3,\"apparentTemperatureLow\":4,\
+             \"apparentTemperatureLowTime\":5,\"apparentTemperatureMax\":6,\
+             \"apparentTemperatureMaxTime\":7,\"apparentTemperatureMin\":8,\
+             \"apparentTemperatureMinTime\":9,\"cloudCover\":10,\"dewPoint\":11,\
+             \"fireIndex\":12,\"humidity\":13,\"iceAccumulation\":14,\
+             \"icon\":\"rain\",\"liquidAccumulation\":16,\"moonPhase\":17,\
+             \"nearestStormBearing\":18,\"nearestStormDistance\":19,\"ozone\":20,\
+             \"precipAccumulation\":21,\"precipIntensity\":22,\
+             \"precipIntensityMax\":23,\"precipIntensityMaxTime\":24,\
+             \"precipProbability\":25,\"precipType\":\"rain\",\"pressure\":27,\
+             \"smoke\":28,\"snowAccumulation\":29,\"summary\":\"Rain\",\
+             \"sunriseTime\":31,\"sunsetTime\":32,\"temperature\":33,\
+             \"temperatureHigh\":34,\"temperatureHighTime\":35,\"temperatureLow\":36,\
+             \"temperatureLowTime\":37,\"temperatureMax\":38,\
+             \"temperatureMaxTime\":39,\"temperatureMin\":40,\
+             \"temperatureMinTime\":41,\"time\":42,\"uvIndex\":43,\"uvIndexTime\":44,\
+             \"visibility\":45,\"windBearing\":46,\"windGust\":47,\
+             \"windGustTime\":48,\"windSpeed\":49}"
+        );
+
+        assert!(data_point.extra.is_empty());
+        assert!(data_point.apparent_temperature.is_some());
+        assert!(data_point.apparent_temperature_high.is_some());
+        assert!(data_point.apparent_temperature_high_time.is_some());
+        assert!(data_point.apparent_temperature_low.is_some());
+        assert!(data_point.apparent_temperature_low_time.is_some());
+        assert!(data_point.apparent_temperature_max.is_some());
+        assert!(data_point.apparent_temperature_max_time.is_some());
+        assert!(data_point.apparent_temperature_min.is_some());
+        assert!(data_point.apparent_temperature_min_time.is_some());
+        assert!(data_point.cloud_cover.is_some());
+        assert!(data_point.dew_point.is_some());
+        assert!(data_point.fire_index.is_some());
+        assert!(data_point.humidity.is_some());
+        assert!(data_point.ice_accumulation.is_some());
+        assert!(data_point.icon.is_some());
+        assert!(data_point.liquid_accumulation.is_some());
+        assert!(data_point.moon_phase.is_some());
+        assert!(data_point.nearest_storm_bearing.is_some());
+        assert!(data_point.nearest_storm_distance.is_some());
+        assert!(data_point.ozone.is_some());
+        assert!(data_point.precip_accumulation.is_some());
+        assert!(data_point.precip_intensity.is_some());
+        assert!(data_point.precip_intensity_max.is_some());
+        assert!(data_point.precip_intensity_max_time.is_some());
+        assert!(data_point.precip_probability.is_some());
+        assert!(data_point.precip_type.is_some());
+        assert!(data_point.pressure.is_some());
+        assert!(data_point.smoke.is_some());
+        assert!(data_point.snow_accumulation.is_some());
+        assert!(data_point.summary.is_some());
+        assert!(data_point.sunrise_time.is_some());
+        assert!(data_point.sunset_time.is_some());
+        assert!(data_point.temperature.is_some());
+        assert!(data_point.temperature_high.is_some());
+        assert!(data_point.temperature_high_time.is_some());
+        assert!(data_point.temperature_low.is_some());
+        assert!(data_point.temperature_low_time.is_some());
+        assert!(data_point.temperature_max.is_some());
+        assert!(data_point.temperature_max_time.is_some());
+        assert!(data_point.temperature_min.is_some());
+        assert!(data_point.temperature_min_time.is_some());
+        assert!(data_point.uv_index.is_some());
+        assert!(data_point.uv_index_time.is_some());
+        assert!(data_point.visibility.is_some());
+        assert!(data_point.wind_bearing.is_some());
+        assert!(data_point.wind_gust.is_some());
+        assert!(data_point.wind_gust_time.is_some());
+        assert!(data_point.wind_speed.is_some());
+    }
+
+    #[test]
+    fn test_forecast_request_builder_alerts_only() {
+        let request = ForecastRequestBuilder::new(API_KEY, LAT, LONG)
+            .exclude_block(ExcludeBlock::Alerts)
+            .alerts_only()
+            .build();
+
+        assert_eq!(
+            vec![
+                ExcludeBlock::Currently,
+                ExcludeBlock::Minutely,
+                ExcludeBlock::Hourly,
+                ExcludeBlock::Daily
+            ],
+            request.exclude
+        );
+        assert_eq!(
+            Some("exclude=currently%2Cminutely%2Chourly%2Cdaily"),
+            request.url.query()
+        );
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "json")]
+    async fn test_api_client_get_forecast_url() {
+        let transport = FakeTransport { status: StatusCode::OK, body: FAKE_RESPONSE };
+        let api_client = ApiClient::new(&transport);
+
+        let url = ForecastRequestBuilder::new(API_KEY, LAT, LONG).to_url();
+
+        let response = api_client.get_forecast_url(url).await.unwrap();
+
+        assert_eq!(LAT, response.latitude);
+
+        let unavailable = FakeTransport { status: StatusCode::SERVICE_UNAVAILABLE, body: "" };
+
+        let url = Url::parse(FORECAST_URL).unwrap();
+
+        match ApiClient::new(&unavailable).get_forecast_url(url).await {
+            Err(ForecastError::Api(ref err)) => {
+                assert_eq!("Service Unavailable", err.message);
+            },
+            other => panic!("expected an API error, got {:?}", other)
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_request_target_from_url() {
+        let url = ForecastRequestBuilder::new(API_KEY, LAT, LONG).to_url();
+        let target = RequestTarget::from_url(&url);
+
+        assert_eq!(API_KEY, target.api_key);
+        assert_eq!((LAT, LONG), (target.latitude, target.longitude));
+
+        let url = TimeMachineRequestBuilder::new(API_KEY, LAT, LONG, TIME).build().url().clone();
+
+        assert!(matches!(RequestTarget::from_url(&url).endpoint, Endpoint::TimeMachine));
+
+        let url = Url::parse("https://example.com/").unwrap();
+        let target = RequestTarget::from_url(&url);
+
+        assert_eq!("", target.api_key);
+        assert!(target.latitude.is_nan());
+    }
+
+    #[test]
+    fn test_data_point_wind_chill() {
+        let cold = data_point_from_json("{\"time\":0,\"temperature\":0,\"windSpeed\":15}");
+
+        assert!((cold.wind_chill(Units::Imperial).unwrap() - -19.4).abs() < 0.1);
+        assert!(cold.wind_chill(Units::SI).unwrap() < 0.0);
+        assert_eq!(None, cold.wind_chill(Units::Auto));
+
+        let calm = data_point_from_json("{\"time\":0,\"temperature\":0,\"windSpeed\":1}");
+
+        assert_eq!(None, calm.wind_chill(Units::SI));
+
+        let mild = data_point_from_json("{\"time\":0,\"temperature\":15,\"windSpeed\":10}");
+
+        assert_eq!(None, mild.wind_chill(Units::SI));
+    }
+
+    #[test]
+    fn test_data_point_heat_index() {
+        let hot = data_point_from_json("{\"time\":0,\"temperature\":90,\"humidity\":0.5}");
+
+        assert!((hot.heat_index(Units::Imperial).unwrap() - 94.6).abs() < 0.1);
+
+        let hot_celsius =
+            data_point_from_json("{\"time\":0,\"temperature\":32.22,\"humidity\":0.5}");
+
+        assert!((hot_celsius.heat_index(Units::SI).unwrap() - 34.8).abs() < 0.1);
+
+        let mild = data_point_from_json("{\"time\":0,\"temperature\":20,\"humidity\":0.9}");
+
+        assert_eq!(None, mild.heat_index(Units::SI));
+        assert_eq!(None, hot.heat_index(Units::Auto));
+    }
+
+    #[test]
+    fn test_data_block_dominant_icon() {
+        let block = |icons: &str| -> DataBlock {
+            serde_json::from_str(&format!("{{\"data\":[{}]}}", icons)).unwrap()
+        };
+
+        assert_eq!(
+            Some(Icon::Cloudy),
+            block("{\"time\":0,\"icon\":\"rain\"},{\"time\":1,\"icon\":\"cloudy\"},\
+                   {\"time\":2,\"icon\":\"cloudy\"},{\"time\":3}").dominant_icon()
+        );
+
+        assert_eq!(
+            Some(Icon::Snow),
+            block("{\"time\":0,\"icon\":\"snow\"},{\"time\":1,\"icon\":\"rain\"}").dominant_icon()
+        );
+
+        assert_eq!(
+            Some(Icon::ClearNight),
+            block("{\"time\":0,\"icon\":\"clear-night\"},{\"time\":1,\"icon\":\"clear-day\"}")
+                .dominant_icon()
+        );
+
+        assert_eq!(None, block("{\"time\":0}").dominant_icon());
+        assert_eq!(None, block("").dominant_icon());
+    }
+
+    #[test]
+    fn test_data_point_approx_eq() {
+        let data_point = data_point_from_json("{\"time\":0,\"temperature\":0.3,\"icon\":\"rain\"}");
+        let close = data_point_from_json(
+            "{\"time\":0,\"temperature\":0.30000000001,\"icon\":\"rain\"}"
+        );
+
+        assert_ne!(data_point, close);
+        assert!(data_point.approx_eq(&close, 1e-9));
+        assert!(!data_point.approx_eq(&close, 1e-12));
+
+        let other_icon = data_point_from_json("{\"time\":0,\"temperature\":0.3,\"icon\":\"snow\"}");
+        let missing = data_point_from_json("{\"time\":0,\"icon\":\"rain\"}");
+        let other_time = data_point_from_json("{\"time\":1,\"temperature\":0.3,\"icon\":\"rain\"}");
+
+        assert!(!data_point.approx_eq(&other_icon, 1.0));
+        assert!(!data_point.approx_eq(&missing, 1.0));
+        assert!(!data_point.approx_eq(&other_time, 1.0));
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_api_response_approx_eq() {
+        let response = ApiResponse::from_json(
+            "{\"latitude\":6.66,\"longitude\":66.6,\"timezone\":\"UTC\",\"offset\":0,\
+              \"currently\":{\"time\":0,\"temperature\":1.1},\
+              \"hourly\":{\"data\":[{\"time\":0,\"humidity\":0.5},{\"time\":3600}]},\
+              \"flags\":{\"sources\":[],\"nearest-station\":1.5}}"
+        ).unwrap();
+
+        let mut close = response.clone();
+
+        close.latitude += 1e-12;
+        close.currently.as_mut().unwrap().temperature = Some(1.1 + 1e-12);
+        close.hourly.as_mut().unwrap().data[0].humidity = Some(0.5 - 1e-12);
+        close.flags.as_mut().unwrap().nearest_station = Some(1.5 + 1e-12);
+
+        assert_ne!(response, close);
+        assert!(response.approx_eq(&close, 1e-9));
+        assert!(!response.approx_eq(&close, 1e-15));
+
+        let mut fewer_points = response.clone();
+
+        fewer_points.hourly.as_mut().unwrap().data.pop();
+        assert!(!response.approx_eq(&fewer_points, 1.0));
+
+        let mut other_timezone = response.clone();
+
+        other_timezone.timezone = "Europe/London".into();
+        assert!(!response.approx_eq(&other_timezone, 1.0));
+
+        let mut no_hourly = response.clone();
+
+        no_hourly.hourly = None;
+        assert!(!response.approx_eq(&no_hourly, 1.0));
+    }
+
+    #[test]
+    #[cfg(all(feature = "json", feature = "tz"))]
+    fn test_api_response_local_time() {
+        let mut response = ApiResponse::from_json(FAKE_RESPONSE).unwrap();
+
+        response.timezone = "America/New_York".into();
+
+        let local_time = response.local_time(1_516_561_200).unwrap();
+
+        assert_eq!("2018-01-21T14:00:00-05:00", local_time.to_rfc3339());
+
+        response.timezone = "Not/A_Zone".into();
+
+        assert_eq!(None, response.local_time(1_516_561_200));
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_timezone_info() {
+        let response = ApiResponse::from_json(FAKE_RESPONSE).unwrap();
+
+        assert_eq!("UTC", response.timezone);
+        assert_eq!(Timezone::from("UTC"), response.timezone_info());
+        assert_eq!("UTC", response.timezone_info().to_string());
+        assert_eq!(FAKE_RESPONSE, response.to_json().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "tz")]
+    fn test_timezone_offset_at() {
+        let timezone = Timezone::from("America/New_York");
+
+        // the night of 2018-03-11 spans the switch from EST to EDT
+        assert_eq!(Some(-5 * 3600), timezone.offset_at(1_520_748_000));
+        assert_eq!(Some(-4 * 3600), timezone.offset_at(1_520_791_200));
+
+        assert_eq!(Some(0), Timezone::from("UTC").offset_at(0));
+        assert_eq!(None, Timezone::from("Not/A_Zone").offset_at(0));
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "json")]
+    async fn test_api_client_max_response_bytes() {
+        let transport = FakeTransport { status: StatusCode::OK, body: FAKE_RESPONSE };
+        let request = ForecastRequestBuilder::new(API_KEY, LAT, LONG).build();
+
+        let api_client = ApiClientBuilder::new(&transport)
+            .max_response_bytes(FAKE_RESPONSE.len())
+            .build();
+
+        assert!(api_client.get_forecasts(vec![request.clone()]).await[0].is_ok());
+
+        let api_client = ApiClientBuilder::new(&transport)
+            .max_response_bytes(FAKE_RESPONSE.len() - 1)
+            .build();
+
+        let responses = api_client.get_forecasts(vec![request.clone()]).await;
+
+        assert!(matches!(responses[0], Err(ForecastError::ResponseTooLarge(limit))
+            if limit == FAKE_RESPONSE.len() - 1));
+
+        let stream = api_client.get_forecast_streaming(&request).await.unwrap();
+        let data_points: Vec<_> = stream.collect().await;
+
+        assert_eq!(1, data_points.len());
+        assert!(matches!(data_points[0], Err(ForecastError::ResponseTooLarge(_))));
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_api_response_warmest_and_coldest_day() {
+        let response = ApiResponse::from_json(
+            "{\"latitude\":6.66,\"longitude\":66.6,\"timezone\":\"UTC\",\"offset\":0,\
+              \"daily\":{\"data\":[\
+                {\"time\":0,\"temperatureHigh\":12.0,\"temperatureLow\":3.0},\
+                {\"time\":86400,\"temperatureLow\":-4.0},\
+                {\"time\":172800,\"temperatureHigh\":18.5},\
+                {\"time\":259200,\"temperatureHigh\":18.5,\"temperatureLow\":-4.0}]}}"
+        ).unwrap();
+
+        assert_eq!(172_800, response.warmest_day().unwrap().time);
+        assert_eq!(86_400, response.coldest_day().unwrap().time);
+
+        let no_daily = ApiResponse::from_json(FAKE_RESPONSE).unwrap();
+
+        assert_eq!(None, no_daily.warmest_day());
+        assert_eq!(None, no_daily.coldest_day());
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_api_response_umbrella_recommended() {
+        let response = ApiResponse::from_json(
+            "{\"latitude\":6.66,\"longitude\":66.6,\"timezone\":\"UTC\",\"offset\":0,\
+              \"hourly\":{\"data\":[\
+                {\"time\":3600,\"precipProbability\":0.1},\
+                {\"time\":7200},\
+                {\"time\":82800,\"precipProbability\":0.35},\
+                {\"time\":90000,\"precipProbability\":0.9}]}}"
+        ).unwrap();
+
+        assert_eq!(Some(0.9), response.hourly.as_ref().unwrap().max_precip_probability());
+
+        assert!(response.umbrella_recommended(0.3));
+        assert!(!response.umbrella_recommended(0.35));
+
+        let no_hourly = ApiResponse::from_json(FAKE_RESPONSE).unwrap();
+
+        assert!(!no_hourly.umbrella_recommended(0.0));
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "json")]
+    async fn test_api_client_api_errors() {
+        let request = ForecastRequestBuilder::new(API_KEY, LAT, LONG).build();
+
+        let forbidden = FakeTransport {
+            status: StatusCode::FORBIDDEN,
+            body: "{\"code\":403,\"error\":\"daily usage limit exceeded\"}"
+        };
+
+        let expected = ApiError {
+            status: StatusCode::FORBIDDEN,
+            code: Some("403".to_string()),
+            message: "daily usage limit exceeded".to_string()
+        };
+
+        match ApiClient::new(&forbidden).get_forecasts(vec![request.clone()]).await.remove(0) {
+            Err(ForecastError::Api(err)) => assert_eq!(expected, err),
+            other => panic!("expected an API error, got {:?}", other)
+        }
+
+        assert_eq!(
+            "API error: daily usage limit exceeded (403 Forbidden, code 403)",
+            ForecastError::Api(expected).to_string()
+        );
+
+        let message = FakeTransport {
+            status: StatusCode::UNAUTHORIZED,
+            body: "{\"message\":\"Invalid API key\"}"
+        };
+
+        match ApiClient::new(&message).get_forecasts(vec![request.clone()]).await.remove(0) {
+            Err(ForecastError::Api(err)) => {
+                assert_eq!(None, err.code);
+                assert_eq!("Invalid API key", err.message);
+            },
+            other => panic!("expected an API error, got {:?}", other)
+        }
 
-    static API_KEY: &'static str = "some_api_key";
+        let plain = FakeTransport { status: StatusCode::BAD_GATEWAY, body: "upstream down\n" };
 
-    // tests for request models and their builders
+        match ApiClient::new(&plain).get_forecasts(vec![request]).await.remove(0) {
+            Err(ForecastError::Api(err)) => assert_eq!("upstream down", err.message),
+            other => panic!("expected an API error, got {:?}", other)
+        }
+    }
 
     #[test]
-    fn test_forecast_request_builder_defaults() {
-        let request = ForecastRequestBuilder::new(API_KEY, LAT, LONG).build();
-
-        let expected_url = Url::parse(&format!(
-            "{base}/{key}/{lat:.16},{long:.16}?",
-            base = FORECAST_URL,
-            key = API_KEY,
-            lat = LAT,
-            long = LONG
-        )).unwrap();
+    fn test_request_builders_coordinate_precision() {
+        let forecast_url = ForecastRequestBuilder::new(API_KEY, LAT, LONG).to_url();
 
-        let expected = ForecastRequest::new(
-            API_KEY,
-            LAT,
-            LONG,
-            expected_url,
-            Vec::new(),
-            None,
-            None,
-            None
+        assert_eq!(
+            "/forecast/some_api_key/6.660000,66.600000",
+            forecast_url.path()
         );
 
-        assert_eq!(expected.api_key, request.api_key);
-        assert_eq!(expected.latitude, request.latitude);
-        assert_eq!(expected.longitude, request.longitude);
-        assert_eq!(expected.exclude, request.exclude);
-        assert_eq!(expected.extend, request.extend);
-        assert_eq!(expected.lang, request.lang);
-        assert_eq!(expected.units, request.units);
-        assert_eq!(expected.url, request.url);
+        let forecast_url = ForecastRequestBuilder::new(API_KEY, LAT, LONG)
+            .coordinate_precision(2)
+            .to_url();
 
-        assert_eq!(expected, request);
+        assert_eq!("/forecast/some_api_key/6.66,66.60", forecast_url.path());
+
+        let time_machine_url = TimeMachineRequestBuilder::new(API_KEY, LAT, LONG, TIME)
+            .coordinate_precision(1)
+            .to_url();
+
+        assert_eq!("/forecast/some_api_key/6.7,66.6,666", time_machine_url.path());
     }
 
-    #[test]
-    fn test_forecast_request_builder_simple() {
-        let mut blocks = vec![ExcludeBlock::Daily, ExcludeBlock::Alerts];
+    #[tokio::test]
+    #[cfg(feature = "json")]
+    async fn test_api_client_requested_lang() {
+        let transport = FakeTransport { status: StatusCode::OK, body: FAKE_RESPONSE };
+        let api_client = ApiClient::new(&transport);
 
-        let request = ForecastRequestBuilder::new(API_KEY, LAT, LONG)
-            .exclude_block(ExcludeBlock::Hourly)
-            .exclude_blocks(&mut blocks)
-            .extend(ExtendBy::Hourly)
-            .lang(Lang::Arabic)
-            .units(Units::Imperial)
-            .build();
+        let requests = vec![
+            ForecastRequestBuilder::new(API_KEY, LAT, LONG).lang(Lang::German).build(),
+            ForecastRequestBuilder::new(API_KEY, LAT, LONG).build()
+        ];
 
-        let expected_url = {
-            let mut url = Url::parse(&format!(
-                "{base}/{key}/{lat:.16},{long:.16}",
-                base = FORECAST_URL,
-                key = API_KEY,
-                lat = LAT,
-                long = LONG
-            )).unwrap();
+        let german = api_client.get_forecast_parsed(&requests[0]).await.unwrap();
+        let default = api_client.get_forecast_parsed(&requests[1]).await.unwrap();
 
-            url.query_pairs_mut()
-                .append_pair(EXCLUDE, "hourly,daily,alerts")
-                .append_pair(EXTEND, "hourly")
-                .append_pair(LANG, "ar")
-                .append_pair(UNITS, "us");
+        assert_eq!(Some(Lang::German), german.requested_lang);
+        assert_eq!(None, default.requested_lang);
 
-            url
-        };
+        let parsed = ApiResponse::from_json(FAKE_RESPONSE).unwrap();
 
-        let expected = ForecastRequest::new(
-            API_KEY,
-            LAT,
-            LONG,
-            expected_url,
-            vec![
-                ExcludeBlock::Hourly,
-                ExcludeBlock::Daily,
-                ExcludeBlock::Alerts
-            ],
-            Some(ExtendBy::Hourly),
-            Some(Lang::Arabic),
-            Some(Units::Imperial)
-        );
+        assert_eq!(parsed, german.response);
+        assert!(parsed.approx_eq(&german.response, 0.0));
+        assert_eq!(api_client.get_forecasts(requests).await[0].as_ref().unwrap(), &parsed);
 
-        assert_eq!(expected, request);
+        let time_machine = TimeMachineRequestBuilder::new(API_KEY, LAT, LONG, TIME)
+            .lang(Lang::French)
+            .build();
+
+        assert_eq!(
+            Some(Lang::French),
+            api_client.get_time_machine_parsed(&time_machine).await.unwrap().requested_lang
+        );
     }
 
     #[test]
-    fn test_forecast_request_builder_complex() {
-        let mut builder = ForecastRequestBuilder::new(API_KEY, LAT, LONG);
-        let mut blocks = vec![ExcludeBlock::Daily, ExcludeBlock::Alerts];
+    fn test_exclude_set() {
+        let mut set = ExcludeSet::from(vec![ExcludeBlock::Flags, ExcludeBlock::Hourly]);
+
+        assert!(set.insert(ExcludeBlock::Currently));
+        assert!(!set.insert(ExcludeBlock::Hourly));
+        assert_eq!(3, set.len());
+        assert!(set.contains(&ExcludeBlock::Flags));
+        assert!(!set.contains(&ExcludeBlock::Daily));
+
+        assert_eq!(
+            vec![ExcludeBlock::Currently, ExcludeBlock::Hourly, ExcludeBlock::Flags],
+            Vec::from(set)
+        );
 
-        builder = builder.exclude_block(ExcludeBlock::Hourly);
-        builder = builder.exclude_blocks(&mut blocks);
-        builder = builder.extend(ExtendBy::Hourly);
-        builder = builder.lang(Lang::Arabic);
-        builder = builder.units(Units::Imperial);
+        assert!(set.remove(&ExcludeBlock::Hourly));
+        assert!(!set.remove(&ExcludeBlock::Hourly));
+        assert_eq!(vec![ExcludeBlock::Currently, ExcludeBlock::Flags], set.to_vec());
 
-        let expected_url = {
-            let mut url = Url::parse(&format!(
-                "{base}/{key}/{lat:.16},{long:.16}",
-                base = FORECAST_URL,
-                key = API_KEY,
-                lat = LAT,
-                long = LONG
-            )).unwrap();
+        set.clear();
+        assert!(set.is_empty());
+        assert_eq!(ExcludeSet::new(), set);
+    }
 
-            url.query_pairs_mut()
-                .append_pair(EXCLUDE, "hourly,daily,alerts")
-                .append_pair(EXTEND, "hourly")
-                .append_pair(LANG, "ar")
-                .append_pair(UNITS, "us");
+    #[test]
+    fn test_request_builders_is_excluded() {
+        let builder = ForecastRequestBuilder::new(API_KEY, LAT, LONG)
+            .exclude_block(ExcludeBlock::Daily)
+            .exclude_block(ExcludeBlock::Daily);
 
-            url
-        };
+        assert!(builder.is_excluded(&ExcludeBlock::Daily));
+        assert!(!builder.is_excluded(&ExcludeBlock::Hourly));
+        assert_eq!(vec![ExcludeBlock::Daily], builder.build().exclude);
 
-        let expected = ForecastRequest::new(
-            API_KEY,
-            LAT,
-            LONG,
-            expected_url,
-            vec![
-                ExcludeBlock::Hourly,
-                ExcludeBlock::Daily,
-                ExcludeBlock::Alerts
-            ],
-            Some(ExtendBy::Hourly),
-            Some(Lang::Arabic),
-            Some(Units::Imperial)
-        );
+        let builder = TimeMachineRequestBuilder::new(API_KEY, LAT, LONG, TIME)
+            .only(ExcludeBlock::Currently);
 
-        assert_eq!(expected, builder.build());
+        assert!(!builder.is_excluded(&ExcludeBlock::Currently));
+        assert!(builder.is_excluded(&ExcludeBlock::Flags));
     }
 
     #[test]
-    fn test_time_machine_request_builder_defaults() {
-        let request = TimeMachineRequestBuilder::new(
-            API_KEY, LAT, LONG, TIME
-        ).build();
+    fn test_data_point_kelvin() {
+        let data_point = data_point_from_json(
+            "{\"time\":0,\"temperature\":20.0,\"apparentTemperature\":-40.0,\
+              \"temperatureHigh\":32.0,\"dewPoint\":0.0}"
+        );
 
-        let expected_url = Url::parse(&format!(
-            "{base}/{key}/{lat:.16},{long:.16},{time}?",
-            base = FORECAST_URL,
-            key = API_KEY,
-            lat = LAT,
-            long = LONG,
-            time = TIME
-        )).unwrap();
+        assert_eq!(Some(293.15), data_point.temperature_kelvin(Units::SI));
+        assert_eq!(Some(273.15), data_point.temperature_high_kelvin(Units::Imperial));
+        assert_eq!(Some(273.15), data_point.dew_point_kelvin(Units::CA));
 
-        let expected = TimeMachineRequest::new(
-            API_KEY,
-            LAT,
-            LONG,
-            TIME,
-            expected_url,
-            Vec::new(),
-            None,
-            None
-        );
+        // -40 is the same in both scales
+        for &units in &[Units::UK, Units::Imperial] {
+            let apparent = data_point.apparent_temperature_kelvin(units).unwrap();
 
-        assert_eq!(expected.api_key, request.api_key);
-        assert_eq!(expected.latitude, request.latitude);
-        assert_eq!(expected.longitude, request.longitude);
-        assert_eq!(expected.time, request.time);
-        assert_eq!(expected.exclude, request.exclude);
-        assert_eq!(expected.lang, request.lang);
-        assert_eq!(expected.units, request.units);
-        assert_eq!(expected.url, request.url);
+            assert!((apparent - 233.15).abs() < 1e-9);
+        }
 
-        assert_eq!(expected, request);
+        assert_eq!(None, data_point.temperature_low_kelvin(Units::SI));
+        assert_eq!(None, data_point.temperature_kelvin(Units::Auto));
     }
 
     #[test]
-    fn test_time_machine_request_builder_simple() {
-        let mut blocks = vec![ExcludeBlock::Daily, ExcludeBlock::Alerts];
-
-        let request = TimeMachineRequestBuilder::new(API_KEY, LAT, LONG, TIME)
-            .exclude_block(ExcludeBlock::Hourly)
-            .exclude_blocks(&mut blocks)
-            .lang(Lang::Arabic)
-            .units(Units::Imperial)
-            .build();
+    #[cfg(feature = "json")]
+    fn test_api_response_is_reliable() {
+        let response = |flags: &str| -> ApiResponse {
+            ApiResponse::from_json(&format!(
+                "{{\"latitude\":6.66,\"longitude\":66.6,\"timezone\":\"UTC\",\"offset\":0{}}}",
+                flags
+            )).unwrap()
+        };
 
-        let expected_url = {
-            let mut url = Url::parse(&format!(
-                "{base}/{key}/{lat:.16},{long:.16},{time}",
-                base = FORECAST_URL,
-                key = API_KEY,
-                lat = LAT,
-                long = LONG,
-                time = TIME
-            )).unwrap();
+        let si = response(",\"flags\":{\"sources\":[],\"nearest-station\":12.0,\"units\":\"si\"}");
 
-            url.query_pairs_mut()
-                .append_pair(EXCLUDE, "hourly,daily,alerts")
-                .append_pair(LANG, "ar")
-                .append_pair(UNITS, "us");
+        assert!(si.is_reliable(12.0));
+        assert!(!si.is_reliable(11.9));
 
-            url
-        };
+        // 12 miles is about 19.3 km
+        let us = response(",\"flags\":{\"sources\":[],\"nearest-station\":12.0,\"units\":\"us\"}");
 
-        let expected = TimeMachineRequest::new(
-            API_KEY,
-            LAT,
-            LONG,
-            TIME,
-            expected_url,
-            vec![
-                ExcludeBlock::Hourly,
-                ExcludeBlock::Daily,
-                ExcludeBlock::Alerts
-            ],
-            Some(Lang::Arabic),
-            Some(Units::Imperial)
-        );
+        assert!(us.is_reliable(19.5));
+        assert!(!us.is_reliable(19.0));
 
-        assert_eq!(expected, request);
+        assert!(response(",\"flags\":{\"sources\":[],\"nearest-station\":12.0}").is_reliable(1.0));
+        assert!(response(",\"flags\":{\"sources\":[],\"units\":\"si\"}").is_reliable(1.0));
+        assert!(response("").is_reliable(1.0));
     }
 
     #[test]
-    fn test_time_machine_request_builder_complex() {
-        let mut builder = TimeMachineRequestBuilder::new(API_KEY, LAT, LONG, TIME);
-        let mut blocks = vec![ExcludeBlock::Daily, ExcludeBlock::Alerts];
+    fn test_forecast_request_builder_build_for_coords() {
+        let builder = ForecastRequestBuilder::new(API_KEY, 0.0, 0.0)
+            .units(Units::SI)
+            .exclude_block(ExcludeBlock::Minutely);
 
-        builder = builder.exclude_block(ExcludeBlock::Hourly);
-        builder = builder.exclude_blocks(&mut blocks);
-        builder = builder.lang(Lang::Arabic);
-        builder = builder.units(Units::Imperial);
+        let requests = builder.build_for_coords(&[(LAT, LONG), (91.0, LONG), (LAT, f64::NAN)]);
 
-        let expected_url = {
-            let mut url = Url::parse(&format!(
-                "{base}/{key}/{lat:.16},{long:.16},{time}",
-                base = FORECAST_URL,
-                key = API_KEY,
-                lat = LAT,
-                long = LONG,
-                time = TIME
-            )).unwrap();
+        assert_eq!(3, requests.len());
 
-            url.query_pairs_mut()
-                .append_pair(EXCLUDE, "hourly,daily,alerts")
-                .append_pair(LANG, "ar")
-                .append_pair(UNITS, "us");
+        let request = requests[0].as_ref().unwrap();
 
-            url
-        };
+        assert_eq!(builder.clone().with_coordinates(LAT, LONG).build(), *request);
 
-        let expected = TimeMachineRequest::new(
-            API_KEY,
-            LAT,
-            LONG,
-            TIME,
-            expected_url,
-            vec![
-                ExcludeBlock::Hourly,
-                ExcludeBlock::Daily,
-                ExcludeBlock::Alerts
-            ],
-            Some(Lang::Arabic),
-            Some(Units::Imperial)
-        );
+        for request in &requests[1..] {
+            assert!(matches!(*request, Err(ForecastError::InvalidRequest(_))));
+        }
 
-        assert_eq!(expected, builder.build());
+        let contradictory = builder.exclude_block(ExcludeBlock::Hourly).extend(ExtendBy::Hourly);
+
+        assert!(contradictory.build_for_coords(&[(LAT, LONG)])[0].is_err());
     }
 
-    // Test that we can deserialize and serialize both variants "nb" and "no"
-    #[test]
-    fn test_norwegian_lang_serde() {
+    #[tokio::test]
+    #[cfg(feature = "json")]
+    async fn test_weather_provider() {
+        let transport = FakeTransport { status: StatusCode::OK, body: FAKE_RESPONSE };
 
-        #[derive(Serialize, Deserialize, PartialEq, Debug)]
-        struct TestStruct {
-            no: Lang,
-            nb: Lang,
-            en: Lang
-        }
+        let providers: Vec<Box<dyn WeatherProvider>> = vec![
+            Box::new(ApiClient::new(&transport)),
+            Box::new(CachingApiClient::new(&transport, Duration::from_secs(60)))
+        ];
 
-        let test_json = "{\"nb\":\"nb\",\"no\":\"no\",\"en\":\"en\"}";
+        let forecast_request = ForecastRequestBuilder::new(API_KEY, LAT, LONG).build();
+        let time_machine_request = TimeMachineRequestBuilder::new(API_KEY, LAT, LONG, TIME).build();
 
-        let test_struct: TestStruct = serde_json::from_str(test_json).unwrap();
+        for provider in &providers {
+            let response = provider.get_forecast(&forecast_request).await.unwrap();
 
-        assert_eq!(test_struct.nb, Lang::NorwegianBokmal);
-        assert_eq!(test_struct.no, Lang::NorwegianBokmal);
-        assert_eq!(test_struct.en, Lang::English);
+            assert_eq!(LAT, response.latitude);
 
-        let test_struct_serialized = serde_json::to_string(&test_struct).unwrap();
+            let response = provider.get_time_machine(&time_machine_request).await.unwrap();
 
-        let test_struct_deserialized: TestStruct = serde_json::from_str(
-            test_struct_serialized.as_str()
-        ).unwrap();
+            assert_eq!(LONG, response.longitude);
+        }
+    }
 
-        assert_eq!(test_struct_deserialized.nb, Lang::NorwegianBokmal);
-        assert_eq!(test_struct_deserialized.no, Lang::NorwegianBokmal);
-        assert_eq!(test_struct_deserialized.en, Lang::English);
+    #[cfg(feature = "geocode")]
+    struct FakeGeocoder;
+
+    #[cfg(feature = "geocode")]
+    impl Geocoder for FakeGeocoder {
+        fn geocode<'a>(&'a self, name: &'a str) -> GeocodeFuture<'a> {
+            let place = |name: &str, latitude, longitude| {
+                Place { name: name.to_string(), latitude, longitude }
+            };
+
+            let places = match name {
+                "Berlin" => vec![
+                    place("Berlin, Germany", 52.52, 13.405),
+                    place("Berlin, New Hampshire", 44.4687, -71.1851)
+                ],
+                "Cambridge, Massachusetts" => vec![place(name, LAT, LONG)],
+                "unreachable" => return Box::pin(async { Err("connection refused".into()) }),
+                _ => Vec::new()
+            };
+
+            Box::pin(async move { Ok(places) })
+        }
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "geocode")]
+    async fn test_forecast_request_builder_from_place() {
+        let request = ForecastRequestBuilder::from_place(
+            API_KEY,
+            "Cambridge, Massachusetts",
+            &FakeGeocoder
+        ).await.unwrap().build();
+
+        assert_eq!(ForecastRequestBuilder::new(API_KEY, LAT, LONG).build(), request);
+
+        match ForecastRequestBuilder::from_place(API_KEY, "Berlin", &FakeGeocoder).await {
+            Err(ForecastError::Geocode(GeocodeError::Ambiguous(ref name, ref places))) => {
+                assert_eq!("Berlin", name);
+                assert_eq!(2, places.len());
+            },
+            other => panic!("expected an ambiguous place, got {:?}", other)
+        }
+
+        match ForecastRequestBuilder::from_place(API_KEY, "Atlantis", &FakeGeocoder).await {
+            Err(err @ ForecastError::Geocode(GeocodeError::NotFound(_))) => {
+                assert_eq!("geocoding error: no place named \"Atlantis\"", err.to_string());
+            },
+            other => panic!("expected no place, got {:?}", other)
+        }
+
+        match ForecastRequestBuilder::from_place(API_KEY, "unreachable", &FakeGeocoder).await {
+            Err(ForecastError::Geocode(GeocodeError::Geocoder(ref err))) => {
+                assert_eq!("connection refused", err.to_string());
+            },
+            other => panic!("expected a geocoder error, got {:?}", other)
+        }
     }
 }
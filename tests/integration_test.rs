@@ -12,6 +12,9 @@ WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 See the License for the specific language governing permissions and
 limitations under the License.*/
 
+// the network tests below are only compiled with the `integration` feature
+#![cfg_attr(not(feature = "integration"), allow(unused_imports, dead_code))]
+
 use std::fs::File;
 use std::path::{PathBuf, Path};
 use std::time::Duration;
@@ -20,7 +23,7 @@ use reqwest::{Client, StatusCode};
 
 use forecast::{ApiResponse, ApiClient, ForecastRequestBuilder,
                TimeMachineRequestBuilder, ExcludeBlock, ExtendBy,
-               Lang, Units};
+               Icon, Lang, PrecipType, Units};
 
 // constants
 
@@ -33,7 +36,7 @@ const TIMEOUT_SECS: u64 = 60;
 // tests for serde models
 
 fn test_response_serde(path: &Path) {
-    let file = match File::open(&path) {
+    let file = match File::open(path) {
         Err(reason) => panic!("couldn't open {}: {}", path.display(), reason),
         Ok(file) => file
     };
@@ -67,6 +70,36 @@ fn test_response_serde_01_21_2018() {
     test_response_serde(path);
 }
 
+#[test]
+fn test_response_serde_full() {
+    let mut path_buf = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path_buf.push("resources/tests/forecast_response_full.json");
+
+    let path = path_buf.as_path();
+
+    test_response_serde(path);
+}
+
+#[test]
+fn test_response_serde_currently_only() {
+    let mut path_buf = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path_buf.push("resources/tests/forecast_response_currently_only.json");
+
+    let path = path_buf.as_path();
+
+    test_response_serde(path);
+}
+
+#[test]
+fn test_response_serde_darksky_unavailable() {
+    let mut path_buf = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path_buf.push("resources/tests/forecast_response_darksky_unavailable.json");
+
+    let path = path_buf.as_path();
+
+    test_response_serde(path);
+}
+
 // tests which perform network calls.
 //
 // To execute these tests, run the following command in the project
@@ -278,3 +311,68 @@ async fn test_get_time_machine_request_default_asref() {
     // FORECAST_API_KEY=$YOUR_FORECAST_API_KEY cargo test --features integration -- --nocapture
     println!("{}", serde_json::to_string_pretty(&api_response).unwrap());
 }
+
+#[tokio::test]
+#[cfg(feature = "integration")]
+async fn test_get_forecasts() {
+    let api_key = env!("FORECAST_API_KEY");
+
+    let reqwest_client = Client::builder()
+        .timeout(Duration::from_secs(TIMEOUT_SECS))
+        .build()
+        .unwrap();
+
+    let api_client = ApiClient::new(&reqwest_client);
+
+    let forecast_requests = vec![
+        ForecastRequestBuilder::new(api_key, LAT, LONG).build(),
+        ForecastRequestBuilder::new(api_key, LONG / 2.0, LAT).build()
+    ];
+
+    let responses = api_client.get_forecasts_with_concurrency(forecast_requests, 2).await;
+
+    assert_eq!(responses.len(), 2);
+
+    let first = responses[0].as_ref().unwrap();
+    let second = responses[1].as_ref().unwrap();
+
+    assert_eq!(first.latitude, LAT);
+    assert_eq!(first.longitude, LONG);
+    assert_eq!(second.latitude, LONG / 2.0);
+    assert_eq!(second.longitude, LAT);
+}
+
+// the API-driven enums are non-exhaustive, so matches outside the crate need a
+// wildcard arm
+
+#[test]
+fn test_non_exhaustive_enums_match_with_wildcard() {
+    let units = match Units::SI {
+        Units::Imperial => "imperial",
+        Units::SI | Units::CA | Units::UK => "metric",
+        _ => "other"
+    };
+
+    assert_eq!("metric", units);
+
+    let icon = match Icon::Fog {
+        Icon::ClearDay | Icon::ClearNight => "clear",
+        _ => "other"
+    };
+
+    assert_eq!("other", icon);
+
+    let precip_type = match PrecipType::Snow {
+        PrecipType::Rain => "rain",
+        _ => "frozen"
+    };
+
+    assert_eq!("frozen", precip_type);
+
+    let lang = match Lang::French {
+        Lang::English => "en",
+        _ => "other"
+    };
+
+    assert_eq!("other", lang);
+}